@@ -0,0 +1,112 @@
+use std::rc::Rc;
+
+use crate::{vectors::vec3::{Vec3, random_in_unit_disk}, environment::environment_map::{luminance, sample_cdf}, utility::{image::Image, rtweekend::{random_number, PI}}};
+
+/**
+ * Shape of the lens opening, which gives out of focus highlights their shape. The shape is scaled by
+ * the lens radius of the camera.
+ */
+#[derive(Clone)]
+pub enum Aperture
+{
+    Disk,
+    Polygon { blades: u32, rotation: f32 }, // Regular polygon from the blades of an iris, rotated by degrees
+    Mask(Rc<Mask>) // Opening drawn as an image
+}
+
+/**
+ * An opening drawn as an image over [-1,1] x [-1,1], where brighter pixels let through more light.
+ * Points are picked by the luminance of the pixels, so a dim mask does not darken the image, only its
+ * shape matters like for the other apertures.
+ */
+pub struct Mask
+{
+    width: usize,
+    height: usize,
+    row_cdf: Vec<f32>, // Cumulative luminance of the rows, from the top
+    column_cdfs: Vec<Vec<f32>> // Cumulative probability of the pixels within each row
+}
+
+impl Mask
+{
+    pub fn new(image: &Image) -> Mask
+    {
+        let (width, height) = (image.width(), image.height());
+
+        let mut row_cdf = Vec::with_capacity(height);
+        let mut column_cdfs = Vec::with_capacity(height);
+        let mut sum = 0.0;
+        for y in 0..height
+        {
+            let row: Vec<f32> = (0..width).map(|x| luminance(&image.pixel(x, y)).max(0.0)).collect();
+            let row_total: f32 = row.iter().sum();
+            let mut row_sum = 0.0;
+            column_cdfs.push(row.iter().map(|p| {
+                row_sum += p;
+                if row_total > 0.0 { row_sum / row_total } else { 0.0 }
+            }).collect());
+            sum += row_total;
+            row_cdf.push(sum);
+        }
+
+        Mask
+        {
+            width,
+            height,
+            row_cdf,
+            column_cdfs
+        }
+    }
+
+    /**
+     * Returns a random point on the opening, None if the mask is black all over
+     */
+    fn sample(&self) -> Option<Vec3>
+    {
+        let total = *self.row_cdf.last()?;
+        if total <= 0.0
+        {
+            return None;
+        }
+
+        let y = sample_cdf(&self.row_cdf, random_number() * total);
+        let x = sample_cdf(&self.column_cdfs[y], random_number());
+        let u = (x as f32 + random_number()) / self.width as f32;
+        let v = 1.0 - (y as f32 + random_number()) / self.height as f32;
+        Some(Vec3::new(2.0 * u - 1.0, 2.0 * v - 1.0, 0.0))
+    }
+}
+
+impl Aperture
+{
+    /**
+     * Returns a random point on the opening, within [-1,1] in x and y. None if an image mask has
+     * no opening.
+     */
+    pub fn sample(&self) -> Option<Vec3>
+    {
+        match self
+        {
+            Aperture::Disk => Some(random_in_unit_disk()),
+            Aperture::Polygon { blades, rotation } => {
+                // Pick one of the triangles between the center and an edge, then a point in it
+                let blades = (*blades).max(3);
+                let step = 2.0 * PI as f32 / blades as f32;
+                let edge = ((random_number() * blades as f32) as u32).min(blades - 1);
+                let angle = rotation.to_radians() + step * edge as f32;
+                let a = Vec3::new(angle.cos(), angle.sin(), 0.0);
+                let b = Vec3::new((angle + step).cos(), (angle + step).sin(), 0.0);
+
+                let mut r1 = random_number();
+                let mut r2 = random_number();
+                if r1 + r2 > 1.0
+                {
+                    r1 = 1.0 - r1;
+                    r2 = 1.0 - r2;
+                }
+                Some(a.const_mul(r1) + b.const_mul(r2))
+            }
+            Aperture::Mask(mask) => mask.sample()
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use crate::vectors::ray::Ray;
+
+/**
+ * Implementation for camera projections
+ */
+pub trait Camera
+{
+    /**
+     * Returns the ray through the image at (s,t), both in [0,1] from the lower left corner.
+     * None if the point is outside the area the projection covers, which is left black.
+     */
+    fn get_ray(&self, s: f32, t: f32) -> Option<Ray>;
+}
@@ -0,0 +1,80 @@
+use crate::{vectors::vec3::{Point3, Vec3}, utility::animation::Track};
+
+use super::perspective::Perspective;
+
+/**
+ * Keyframed settings for a perspective camera, giving the camera to render each moment of an animation with
+ */
+pub struct CameraAnimation
+{
+    lookfrom: Track<Point3>,
+    lookat: Track<Point3>,
+    vup: Vec3,
+    aspect_ratio: f32,
+    vfov: Track<f32>, // vertical field-of-view in degrees
+    aperture: Track<f32>,
+    focus_dist: Option<Track<f32>> // Focused on lookat if None
+}
+
+impl CameraAnimation
+{
+    /**
+     * Creates a camera moving along the given tracks, with a 20 degree field of view, no depth of field
+     * and lookat in focus
+     */
+    pub fn new(lookfrom: Track<Point3>, lookat: Track<Point3>, vup: Vec3, aspect_ratio: f32) -> CameraAnimation
+    {
+        CameraAnimation
+        {
+            lookfrom,
+            lookat,
+            vup,
+            aspect_ratio,
+            vfov: Track::constant(20.0),
+            aperture: Track::constant(0.0),
+            focus_dist: None
+        }
+    }
+
+    pub fn with_vfov(self, vfov: Track<f32>) -> CameraAnimation
+    {
+        CameraAnimation
+        {
+            vfov,
+            ..self
+        }
+    }
+
+    pub fn with_aperture(self, aperture: Track<f32>) -> CameraAnimation
+    {
+        CameraAnimation
+        {
+            aperture,
+            ..self
+        }
+    }
+
+    pub fn with_focus_dist(self, focus_dist: Track<f32>) -> CameraAnimation
+    {
+        CameraAnimation
+        {
+            focus_dist: Some(focus_dist),
+            ..self
+        }
+    }
+
+    /**
+     * Returns the camera at the given time
+     */
+    pub fn at(&self, time: f32) -> Perspective
+    {
+        let lookfrom = self.lookfrom.at(time);
+        let lookat = self.lookat.at(time);
+        let focus_dist = match &self.focus_dist {
+            Some(track) => track.at(time),
+            None => (lookfrom - lookat).length()
+        };
+
+        Perspective::new(lookfrom, lookat, self.vup, self.vfov.at(time), self.aspect_ratio, self.aperture.at(time).max(0.0), focus_dist)
+    }
+}
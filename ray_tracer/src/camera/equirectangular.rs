@@ -0,0 +1,48 @@
+use crate::{vectors::{vec3::{Point3, Vec3, cross}, ray::Ray}, utility::rtweekend::PI};
+
+use super::camera::Camera;
+
+/**
+ * A 360 degree panorama. The horizontal axis is the angle around vup and the vertical axis the elevation,
+ * with lookat in the middle of the image. Best used with an aspect ratio of 2:1.
+ */
+pub struct Equirectangular
+{
+    origin: Point3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3
+}
+
+impl Equirectangular
+{
+    pub fn new(lookfrom: Point3, lookat: Point3, vup: Vec3) -> Equirectangular
+    {
+        // The horizon is kept level, so w lies in the plane perpendicular to vup
+        let v = vup.unit_vector();
+        let u = (cross(&v, &(lookfrom - lookat))).unit_vector();
+        let w = cross(&u, &v);
+
+        Equirectangular
+        {
+            origin: lookfrom,
+            u,
+            v,
+            w
+        }
+    }
+}
+
+impl Camera for Equirectangular
+{
+    fn get_ray(&self, s: f32, t: f32) -> Option<Ray>
+    {
+        let phi = (s - 0.5) * 2.0 * PI as f32;
+        let elevation = (t - 0.5) * PI as f32;
+
+        let around = self.u.const_mul(phi.sin()) - self.w.const_mul(phi.cos());
+        let direction = around.const_mul(elevation.cos()) + self.v.const_mul(elevation.sin());
+
+        Some(Ray::new(self.origin, direction))
+    }
+}
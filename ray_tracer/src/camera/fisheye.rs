@@ -0,0 +1,59 @@
+use crate::{vectors::{vec3::{Point3, Vec3, cross}, ray::Ray}, utility::rtweekend::degrees_to_radians};
+
+use super::camera::Camera;
+
+/**
+ * An equidistant fisheye, where the distance from the image center is proportional to the angle from
+ * the viewing direction. The image circle fits the height of the image, and covers the given field of view.
+ */
+pub struct Fisheye
+{
+    origin: Point3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+    aspect_ratio: f32,
+    max_angle: f32 // Half the field of view, in radians
+}
+
+impl Fisheye
+{
+    pub fn new(lookfrom: Point3, lookat: Point3, vup: Vec3, fov: f32, aspect_ratio: f32) -> Fisheye
+    {
+        let w = (lookfrom - lookat).unit_vector();
+        let u = (cross(&vup, &w)).unit_vector();
+        let v = cross(&w, &u);
+
+        Fisheye
+        {
+            origin: lookfrom,
+            u,
+            v,
+            w,
+            aspect_ratio,
+            max_angle: degrees_to_radians(fov) as f32 / 2.0
+        }
+    }
+}
+
+impl Camera for Fisheye
+{
+    fn get_ray(&self, s: f32, t: f32) -> Option<Ray>
+    {
+        // Position relative to the image circle, which has radius 1
+        let x = (2.0 * s - 1.0) * self.aspect_ratio;
+        let y = 2.0 * t - 1.0;
+        let r = (x*x + y*y).sqrt();
+        if r > 1.0
+        {
+            return None;
+        }
+
+        let theta = r * self.max_angle;
+        let (cos_phi, sin_phi) = if r > 0.0 { (x / r, y / r) } else { (1.0, 0.0) };
+        let direction = (self.u.const_mul(cos_phi) + self.v.const_mul(sin_phi)).const_mul(theta.sin())
+            - self.w.const_mul(theta.cos());
+
+        Some(Ray::new(self.origin, direction))
+    }
+}
@@ -0,0 +1,9 @@
+pub mod camera;
+pub mod perspective;
+pub mod orthographic;
+pub mod fisheye;
+pub mod equirectangular;
+pub mod aperture;
+pub mod camera_animation;
+#[cfg(feature = "preview")]
+pub mod orbit;
\ No newline at end of file
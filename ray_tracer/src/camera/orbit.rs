@@ -0,0 +1,95 @@
+use std::rc::Rc;
+
+use crate::vectors::{ray::Ray, vec3::{Point3, Vec3, cross, dot, rotate_y}};
+
+use super::camera::Camera;
+
+// Steepest the view is turned up or down, in degrees, so it never flips over the top
+const MAX_PITCH: f32 = 89.0;
+
+/**
+ * Moves another camera around a pivot point: turned around the vertical through the pivot, tilted up
+ * or down over it, and moved closer or further away. The rays of the camera are turned along with it,
+ * so this works with any projection.
+ */
+pub struct Orbit
+{
+    camera: Rc<dyn Camera>,
+    pivot: Point3,
+    right: Vec3, // Horizontal axis across the view of the camera, which tilting turns around
+    yaw: f32, // Degrees around the vertical, to the right
+    pitch: f32, // Degrees up over the pivot
+    distance: f32 // Scale of the distance to the pivot
+}
+
+impl Orbit
+{
+    pub fn new(camera: Rc<dyn Camera>, pivot: Point3) -> Orbit
+    {
+        let forward = camera.get_ray(0.5, 0.5).map_or(Vec3::new(0.0,0.0,-1.0), |ray| ray.direction());
+        let right = cross(&forward, &Vec3::new(0.0,1.0,0.0));
+        let right = if right.length() > 0.0 { right.unit_vector() } else { Vec3::new(1.0,0.0,0.0) };
+
+        Orbit
+        {
+            camera,
+            pivot,
+            right,
+            yaw: 0.0,
+            pitch: 0.0,
+            distance: 1.0
+        }
+    }
+
+    /**
+     * Moves the camera around the pivot by degrees, to its right and up
+     */
+    pub fn turn(&mut self, yaw: f32, pitch: f32)
+    {
+        self.yaw = (self.yaw + yaw) % 360.0;
+        self.pitch = (self.pitch + pitch).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    /**
+     * Scales the distance to the pivot, below 1 moves closer
+     */
+    pub fn zoom(&mut self, scale: f32)
+    {
+        self.distance *= scale;
+    }
+
+    /**
+     * Moves the camera back to where it started
+     */
+    pub fn reset(&mut self)
+    {
+        self.yaw = 0.0;
+        self.pitch = 0.0;
+        self.distance = 1.0;
+    }
+
+    fn rotate(&self, v: &Vec3) -> Vec3
+    {
+        let tilted = rotate_around(v, &self.right, -self.pitch.to_radians());
+        rotate_y(&tilted, self.yaw.to_radians())
+    }
+}
+
+impl Camera for Orbit
+{
+    fn get_ray(&self, s: f32, t: f32) -> Option<Ray>
+    {
+        let ray = self.camera.get_ray(s, t)?;
+        let origin = self.pivot + self.rotate(&(ray.origin() - self.pivot)).const_mul(self.distance);
+        Some(ray.continued(origin, self.rotate(&ray.direction())))
+    }
+}
+
+/**
+ * Rotates the vector by the angle in radians around a unit axis
+ */
+fn rotate_around(v: &Vec3, axis: &Vec3, angle: f32) -> Vec3
+{
+    let (sin, cos) = angle.sin_cos();
+    v.const_mul(cos) + cross(axis, v).const_mul(sin) + axis.const_mul(dot(axis, v) * (1.0 - cos))
+}
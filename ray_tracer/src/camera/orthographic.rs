@@ -0,0 +1,48 @@
+use crate::vectors::{vec3::{Point3, Vec3, cross}, ray::Ray};
+
+use super::camera::Camera;
+
+/**
+ * A parallel projection, where all rays travel in the viewing direction. Sizes do not change with
+ * distance, as in an architectural elevation.
+ */
+pub struct Orthographic
+{
+    lower_left_corner: Point3,
+    horizontal: Vec3,
+    vertical: Vec3,
+    direction: Vec3
+}
+
+impl Orthographic
+{
+    /**
+     * Creates a camera that shows an area of the given height in world units, centered on lookfrom
+     */
+    pub fn new(lookfrom: Point3, lookat: Point3, vup: Vec3, height: f32, aspect_ratio: f32) -> Orthographic
+    {
+        let w = (lookfrom - lookat).unit_vector();
+        let u = (cross(&vup, &w)).unit_vector();
+        let v = cross(&w, &u);
+
+        let horizontal = u.const_mul(height * aspect_ratio);
+        let vertical = v.const_mul(height);
+
+        Orthographic
+        {
+            lower_left_corner: lookfrom - horizontal.const_div(2.0) - vertical.const_div(2.0),
+            horizontal,
+            vertical,
+            direction: w.negate_vec()
+        }
+    }
+}
+
+impl Camera for Orthographic
+{
+    fn get_ray(&self, s: f32, t: f32) -> Option<Ray>
+    {
+        let origin = self.lower_left_corner + self.horizontal.const_mul(s) + self.vertical.const_mul(t);
+        Some(Ray::new(origin, self.direction))
+    }
+}
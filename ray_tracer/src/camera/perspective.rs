@@ -0,0 +1,214 @@
+use crate::{vectors::{vec3::{Point3, Vec3, cross, dot}, ray::Ray}, utility::rtweekend::degrees_to_radians};
+
+use super::{camera::Camera, aperture::Aperture};
+
+/**
+ * A thin lens perspective camera, with depth of field from its aperture. The shape of the aperture can be
+ * changed, the lens barrel can clip it towards the edges of the image and the plane in focus can be tilted.
+ */
+pub struct Perspective
+{
+    aspect_ratio: f32,
+    viewport_height: f32,
+    viewport_width: f32,
+    focal_length: f32,
+
+    origin: Point3,
+    horizontal: Vec3,
+    vertical: Vec3,
+    lower_left_corner: Vec3,
+
+    u: Vec3,
+    v: Vec3, 
+    w: Vec3,
+    lens_radius: f32,
+
+    aperture: Aperture,
+    cat_eye: f32, // How far the lens barrel moves across the aperture at the edge of the image, in lens radii
+    focus_dist: f32,
+    focus_normal: Vec3 // Normal of the plane in focus, w unless the plane is tilted
+}
+
+impl Default for Perspective
+{
+    /**
+     * Creates a camera with the default settings
+     */
+    fn default() -> Perspective
+    {
+        let aspect_ratio = 16.0/9.0;
+        let viewport_height = 2.0;
+        let viewport_width = aspect_ratio * viewport_height;
+        let focal_length = 1.0; 
+        let origin = Point3::new(0.0,0.0,0.0);
+        let horizontal = Vec3::new(viewport_width, 0.0, 0.0);
+        let vertical = Vec3::new(0.0, viewport_height, 0.0);
+        let lower_left_corner = origin - horizontal.const_div(2.0) - vertical.const_div(2.0) - Vec3::new(0.0, 0.0, focal_length);
+
+        Perspective{
+            aspect_ratio: aspect_ratio,
+            viewport_height: viewport_height,
+            viewport_width: viewport_width,
+            focal_length: focal_length,
+            origin: origin,
+            horizontal: horizontal,
+            vertical: vertical,
+            lower_left_corner: lower_left_corner,
+            u: Vec3::default(),
+            v: Vec3::default(),
+            w: Vec3::new(0.0, 0.0, 1.0),
+            lens_radius: 0.0,
+            aperture: Aperture::Disk,
+            cat_eye: 0.0,
+            focus_dist: focal_length,
+            focus_normal: Vec3::new(0.0, 0.0, 1.0)
+        }
+    }
+}
+
+impl Perspective {
+
+    /**
+     * Creates a new camera with given aspect_ratio and vertical fov
+     */
+    pub fn new(lookfrom: Point3,
+               lookat: Point3,
+               vup: Vec3,
+               vfov: f32, // vertical field-of-view in degrees
+               aspect_ratio: f32,
+               aperture: f32,
+               focus_dist: f32
+            ) -> Perspective
+    {
+        // Calculate vfov
+        let theta = degrees_to_radians(vfov);
+        let h = (theta/2.0).tan() as f32;
+        let viewport_height = 2.0 * h;
+        let viewport_width = aspect_ratio * viewport_height;
+
+        let w = (lookfrom - lookat).unit_vector();
+        let u = (cross(&vup, &w)).unit_vector();
+        let v = cross(&w, &u);
+
+        let origin = lookfrom;
+        let horizontal = u.const_mul(viewport_width*focus_dist);
+        let vertical = v.const_mul(viewport_height*focus_dist);
+        let lower_left_corner = origin - horizontal.const_div(2.0) - vertical.const_div(2.0) - (w.const_mul(focus_dist));
+
+        let lens_radius = aperture / 2.0;
+
+        // Set to 1.0 as it not used here.
+        let focal_legnth = 1.0;
+
+        Perspective{
+            aspect_ratio: aspect_ratio,
+            viewport_height: viewport_height,
+            viewport_width: viewport_width,
+            focal_length: 1.0,
+            origin: origin,
+            horizontal: horizontal,
+            vertical: vertical,
+            lower_left_corner: lower_left_corner, 
+            u: u,
+            v: v,
+            w: w,
+            lens_radius: lens_radius,
+            aperture: Aperture::Disk,
+            cat_eye: 0.0,
+            focus_dist,
+            focus_normal: w
+        }
+    }
+
+    /**
+     * Returns the camera with the given aperture shape, keeping the lens radius
+     */
+    pub fn with_aperture(self, aperture: Aperture) -> Perspective
+    {
+        Perspective
+        {
+            aperture,
+            ..self
+        }
+    }
+
+    /**
+     * Returns the camera with cat-eye vignetting. Away from the image center the opening of the lens barrel
+     * covers part of the aperture, moving by the given amount of lens radii at the edge of the image, which
+     * darkens the corners and turns out of focus highlights into cat-eye shapes.
+     */
+    pub fn with_cat_eye(self, amount: f32) -> Perspective
+    {
+        Perspective
+        {
+            cat_eye: amount.max(0.0),
+            ..self
+        }
+    }
+
+    /**
+     * Returns the camera with the plane in focus tilted by the given degrees, around the horizontal axis of the
+     * image (tilt, a positive tilt leans the plane towards the ground) and around its vertical axis (swing).
+     * The plane still goes through the focus distance at the image center.
+     */
+    pub fn with_tilt(self, tilt: f32, swing: f32) -> Perspective
+    {
+        let focus_normal = (self.w
+            + self.v.const_mul(degrees_to_radians(tilt).tan() as f32)
+            + self.u.const_mul(degrees_to_radians(swing).tan() as f32)).unit_vector();
+
+        Perspective
+        {
+            focus_normal,
+            ..self
+        }
+    }
+
+    /**
+     * Returns the camera with the image shifted parallel to the lens, by the given fractions of the image
+     * width and height. Lines parallel to the image stay parallel, as with a shift lens.
+     */
+    pub fn with_shift(self, x: f32, y: f32) -> Perspective
+    {
+        Perspective
+        {
+            lower_left_corner: self.lower_left_corner + self.horizontal.const_mul(x) + self.vertical.const_mul(y),
+            ..self
+        }
+    }
+}
+
+impl Camera for Perspective
+{
+    /**
+     * Creates ray based on given input (u,v)
+     */
+    fn get_ray(&self, s: f32, t: f32) -> Option<Ray>
+    {
+        let lens_point = self.aperture.sample()?;
+
+        // The barrel opening moves across the aperture towards the edges of the image
+        if self.cat_eye > 0.0
+        {
+            let barrel = Vec3::new(1.0 - 2.0 * s, 1.0 - 2.0 * t, 0.0).const_mul(self.cat_eye);
+            if (lens_point - barrel).length_squared() > 1.0
+            {
+                return None;
+            }
+        }
+
+        // Point in focus on the (tilted) focus plane, seen through the center of the lens
+        let pinhole_direction = self.lower_left_corner + self.horizontal.const_mul(s) + self.vertical.const_mul(t) - self.origin;
+        let plane_distance = dot(&self.w.const_mul(-self.focus_dist), &self.focus_normal) / dot(&pinhole_direction, &self.focus_normal);
+
+        let rd: Vec3 = lens_point.const_mul(self.lens_radius);
+        let offset = self.u.const_mul(rd.x()) + self.v.const_mul(rd.y());
+        if plane_distance <= 0.0
+        {
+            // A steeply tilted plane is only met behind the camera, this part of the image is focused at infinity
+            return Some(Ray::new(self.origin + offset, pinhole_direction));
+        }
+        let focus_point = self.origin + pinhole_direction.const_mul(plane_distance);
+        Some(Ray::new(self.origin + offset, focus_point - self.origin - offset))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,289 @@
+use std::{collections::VecDeque, io, io::{BufReader, Write}, net::{TcpListener, TcpStream}, sync::{Arc, Condvar, Mutex, atomic::{AtomicUsize, Ordering}, mpsc, mpsc::{Receiver, RecvTimeoutError, Sender}}, thread, time::Duration};
+
+use crate::{film::{filter::Filter, frame::{Frame, PIXEL_VALUES}, region::Region}, options::Options, preview::terminal::TerminalPreview, IMAGE_HEIGHT, IMAGE_WIDTH};
+
+use super::protocol::{invalid, parse, read_line, read_values, write_line};
+
+// How often the coordinator says it is still waiting while no worker is connected
+const NO_WORKERS_INTERVAL: Duration = Duration::from_secs(10);
+
+/**
+ * Hands out the tiles of each frame to workers that connect over TCP, and puts the frame together from
+ * the parts they send back. Workers may join at any time, and the tiles of a worker that leaves or takes
+ * too long are handed to the others.
+ */
+pub struct Coordinator
+{
+    address: String,
+    workers: Arc<AtomicUsize>, // Workers connected right now
+    queue: Arc<TileQueue>,
+    results: Receiver<TileResult>,
+    tiles: Vec<Region>,
+    filter: Filter,
+    terminal_preview: Option<usize> // Columns of the image shown in the terminal as the tiles come in
+}
+
+/**
+ * A tile of a frame to render
+ */
+#[derive(Copy, Clone)]
+struct Job
+{
+    number: u32,
+    time: f32,
+    index: usize,
+    tile: Region
+}
+
+/**
+ * A rendered tile: the sums of the tile and the pixels around it that its samples were spread into
+ */
+struct TileResult
+{
+    number: u32,
+    index: usize,
+    area: Region,
+    values: Vec<f32>
+}
+
+/**
+ * Tiles waiting for a worker
+ */
+struct TileQueue
+{
+    jobs: Mutex<(VecDeque<Job>, bool)>, // The tiles, and whether the last frame has been handed out
+    ready: Condvar
+}
+
+impl Coordinator
+{
+    /**
+     * Starts listening for workers. The workers are sent the options this process was started with.
+     */
+    pub fn start(address: &str, options: &Options) -> io::Result<Coordinator>
+    {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?.to_string();
+        eprintln!("Waiting for workers on {}", address);
+
+        let args: Vec<String> = options.args.iter().filter(|arg| !arg.starts_with("--coordinator")).cloned().collect();
+        let timeout = Duration::from_secs(options.tile_timeout);
+        let workers = Arc::new(AtomicUsize::new(0));
+        let queue = Arc::new(TileQueue::new());
+        let (sender, results) = mpsc::channel();
+        {
+            let (workers, queue) = (workers.clone(), queue.clone());
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten()
+                {
+                    let (args, workers, queue, sender) = (args.clone(), workers.clone(), queue.clone(), sender.clone());
+                    thread::spawn(move || {
+                        workers.fetch_add(1, Ordering::SeqCst);
+                        serve(stream, &args, timeout, &queue, &sender);
+                        workers.fetch_sub(1, Ordering::SeqCst);
+                    });
+                }
+            });
+        }
+
+        Ok(Coordinator
+        {
+            address,
+            workers,
+            queue,
+            results,
+            tiles: crate::tiles(options),
+            filter: options.filter,
+            terminal_preview: options.terminal_preview
+        })
+    }
+
+    /**
+     * Has the workers render the frame with the given number at the given time
+     */
+    pub fn render(&self, number: u32, time: f32) -> Frame
+    {
+        for (index, tile) in self.tiles.iter().enumerate()
+        {
+            self.queue.push(Job { number, time, index, tile: *tile });
+        }
+
+        let mut results: Vec<Option<TileResult>> = self.tiles.iter().map(|_| None).collect();
+        // The preview adds the tiles as they come in, the frame itself is put together in order below
+        let mut preview = self.terminal_preview.map(|columns| (TerminalPreview::new(columns), Frame::new(IMAGE_WIDTH as usize, IMAGE_HEIGHT as usize, self.filter)));
+        let mut remaining = self.tiles.len();
+        while remaining > 0
+        {
+            // The sender is kept by the thread accepting workers, which never stops
+            let result = match self.results.recv_timeout(NO_WORKERS_INTERVAL) {
+                Ok(result) => result,
+                Err(RecvTimeoutError::Timeout) => {
+                    if self.workers.load(Ordering::SeqCst) == 0
+                    {
+                        eprintln!("\nNo workers connected, start them with --worker={}", self.address);
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => panic!("workers stopped")
+            };
+            if result.number != number || result.index >= results.len() || results[result.index].is_some()
+            {
+                continue;
+            }
+            let index = result.index;
+            if let Some((_, shown)) = &mut preview
+            {
+                shown.add(&result.area, &result.values);
+            }
+            results[index] = Some(result);
+            remaining -= 1;
+            eprint!("\rTiles remaining: {} ", remaining);
+
+            // Show the image so far each time a row of tiles is done
+            if let Some((preview, shown)) = &mut preview
+            {
+                let row = self.tiles[index].y;
+                if self.tiles.iter().zip(&results).all(|(tile, result)| tile.y != row || result.is_some())
+                {
+                    preview.show(&shown.color());
+                }
+            }
+        }
+        eprintln!();
+
+        // Tiles overlap where their samples are spread, they are added in order so the sums come out the same every time
+        let mut frame = Frame::new(IMAGE_WIDTH as usize, IMAGE_HEIGHT as usize, self.filter);
+        for result in results.iter().flatten()
+        {
+            frame.add(&result.area, &result.values);
+        }
+        frame
+    }
+}
+
+impl Drop for Coordinator
+{
+    fn drop(&mut self)
+    {
+        self.queue.finish();
+    }
+}
+
+impl TileQueue
+{
+    fn new() -> TileQueue
+    {
+        TileQueue
+        {
+            jobs: Mutex::new((VecDeque::new(), false)),
+            ready: Condvar::new()
+        }
+    }
+
+    fn push(&self, job: Job)
+    {
+        self.jobs.lock().unwrap().0.push_back(job);
+        self.ready.notify_one();
+    }
+
+    /**
+     * Waits for the next tile, None once all frames have been handed out
+     */
+    fn next(&self) -> Option<Job>
+    {
+        let mut jobs = self.jobs.lock().unwrap();
+        loop
+        {
+            if let Some(job) = jobs.0.pop_front()
+            {
+                return Some(job);
+            }
+            if jobs.1
+            {
+                return None;
+            }
+            jobs = self.ready.wait(jobs).unwrap();
+        }
+    }
+
+    fn finish(&self)
+    {
+        self.jobs.lock().unwrap().1 = true;
+        self.ready.notify_all();
+    }
+}
+
+/**
+ * Hands tiles to one worker until there are no more, or the worker goes away
+ */
+fn serve(stream: TcpStream, args: &[String], timeout: Duration, queue: &TileQueue, results: &Sender<TileResult>)
+{
+    let peer = stream.peer_addr().map_or(String::from("?"), |address| address.to_string());
+    eprintln!("\nWorker {} joined", peer);
+    if let Err(e) = hand_out(stream, args, timeout, queue, results)
+    {
+        eprintln!("\nWorker {} left: {}", peer, e);
+    }
+}
+
+/**
+ * A worker that does not send a tile back within the timeout is given up on, it may have hung
+ */
+fn hand_out(stream: TcpStream, args: &[String], timeout: Duration, queue: &TileQueue, results: &Sender<TileResult>) -> io::Result<()>
+{
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(timeout))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    write_line(&mut writer, &format!("ARGS {}", args.len()))?;
+    for arg in args
+    {
+        write_line(&mut writer, arg)?;
+    }
+
+    while let Some(job) = queue.next()
+    {
+        match render_job(&job, &mut reader, &mut writer) {
+            Ok(result) => {
+                let _ = results.send(result);
+            }
+            Err(e) => {
+                // Another worker renders the tile instead
+                queue.push(job);
+                return match e.kind() {
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Err(io::Error::new(io::ErrorKind::TimedOut,
+                        format!("no tile back within {} seconds", timeout.as_secs()))),
+                    _ => Err(e)
+                };
+            }
+        }
+    }
+    write_line(&mut writer, "DONE")
+}
+
+/**
+ * Sends a tile to the worker and waits for it to come back
+ */
+fn render_job(job: &Job, reader: &mut BufReader<TcpStream>, writer: &mut dyn Write) -> io::Result<TileResult>
+{
+    let tile = job.tile;
+    write_line(writer, &format!("TILE {} {} {} {} {} {} {}", job.number, job.time, job.index, tile.x, tile.y, tile.width, tile.height))?;
+
+    let line = read_line(reader)?;
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    match fields[..]
+    {
+        ["RESULT", number, index, x, y, width, height, count] => {
+            let area = Region { x: parse(x, &line)?, y: parse(y, &line)?, width: parse(width, &line)?, height: parse(height, &line)? };
+            let count: usize = parse(count, &line)?;
+            if area.x + area.width > IMAGE_WIDTH as usize || area.y + area.height > IMAGE_HEIGHT as usize || count != area.width * area.height * PIXEL_VALUES
+            {
+                return Err(invalid(&line));
+            }
+            let values = read_values(reader, count)?;
+            Ok(TileResult { number: parse(number, &line)?, index: parse(index, &line)?, area, values })
+        }
+        _ => Err(invalid(&line))
+    }
+}
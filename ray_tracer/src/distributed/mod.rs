@@ -0,0 +1,3 @@
+pub mod protocol;
+pub mod coordinator;
+pub mod worker;
\ No newline at end of file
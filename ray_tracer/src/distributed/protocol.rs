@@ -0,0 +1,99 @@
+use std::{io, io::{BufRead, Read, Write}, str::FromStr};
+
+/*
+ * Messages between a coordinator and its workers are lines of text, some followed by binary values:
+ *
+ *   ARGS count                                  coordinator to worker, followed by count lines of arguments
+ *   TILE frame time index x y width height      coordinator to worker, a tile to render
+ *   DONE                                        coordinator to worker, no more tiles
+ *   RESULT frame index x y width height count   worker to coordinator, followed by count little endian f32
+ */
+
+pub fn write_line(stream: &mut dyn Write, line: &str) -> io::Result<()>
+{
+    stream.write_all(format!("{}\n", line).as_bytes())?;
+    stream.flush()
+}
+
+/**
+ * Reads the next line, without its line ending. The end of the stream is an UnexpectedEof error.
+ */
+pub fn read_line(stream: &mut dyn BufRead) -> io::Result<String>
+{
+    let mut line = String::new();
+    if stream.read_line(&mut line)? == 0
+    {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed"));
+    }
+    Ok(line.trim_end().to_string())
+}
+
+pub fn write_values(stream: &mut dyn Write, values: &[f32]) -> io::Result<()>
+{
+    let bytes: Vec<u8> = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+    stream.write_all(&bytes)?;
+    stream.flush()
+}
+
+pub fn read_values(stream: &mut dyn Read, count: usize) -> io::Result<Vec<f32>>
+{
+    let mut bytes = vec![0u8; count * 4];
+    stream.read_exact(&mut bytes)?;
+    Ok(bytes.chunks(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect())
+}
+
+/**
+ * Reads a field of a message
+ */
+pub fn parse<T: FromStr>(field: &str, line: &str) -> io::Result<T>
+{
+    field.parse::<T>().map_err(|_| invalid(line))
+}
+
+pub fn invalid(line: &str) -> io::Error
+{
+    io::Error::new(io::ErrorKind::InvalidData, format!("unexpected message: {}", line))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn lines_and_values_round_trip()
+    {
+        let values = [0.0, -1.5, 3.25e-7, f32::MAX, f32::INFINITY];
+        let mut stream = Vec::new();
+        write_line(&mut stream, "RESULT 2 7 0 16 16 16 5").unwrap();
+        write_values(&mut stream, &values).unwrap();
+        write_line(&mut stream, "DONE").unwrap();
+
+        let mut reader = Cursor::new(stream);
+        let line = read_line(&mut reader).unwrap();
+        assert_eq!(line, "RESULT 2 7 0 16 16 16 5");
+        let count: usize = parse(line.split_whitespace().last().unwrap(), &line).unwrap();
+        let read = read_values(&mut reader, count).unwrap();
+        assert_eq!(read.iter().map(|value| value.to_bits()).collect::<Vec<_>>(), values.iter().map(|value| value.to_bits()).collect::<Vec<_>>());
+        assert_eq!(read_line(&mut reader).unwrap(), "DONE");
+        assert_eq!(read_line(&mut reader).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn lines_lose_their_line_ending()
+    {
+        let mut reader = Cursor::new(b"TILE 0 0.5 3 0 0 16 16\r\n".to_vec());
+        assert_eq!(read_line(&mut reader).unwrap(), "TILE 0 0.5 3 0 0 16 16");
+    }
+
+    #[test]
+    fn short_values_and_bad_fields_are_errors()
+    {
+        let mut reader = Cursor::new(vec![0u8; 7]);
+        assert_eq!(read_values(&mut reader, 2).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(parse::<usize>("-3", "TILE -3").unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(parse::<f32>("0.25", "TILE").unwrap(), 0.25);
+    }
+}
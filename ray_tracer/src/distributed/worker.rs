@@ -0,0 +1,59 @@
+use std::{io, io::BufReader, net::TcpStream};
+
+use crate::{film::{frame::Frame, region::Region}, options::Options, utility::rtweekend::seed_random, IMAGE_HEIGHT, IMAGE_WIDTH, SAMPLES_PER_PIXEL};
+
+use super::protocol::{invalid, parse, read_line, write_line, write_values};
+
+/**
+ * Renders tiles for a coordinator until it has no more. The scene is built from the options the coordinator
+ * sends, and each tile starts from its own seed, so it comes out as it would in a render on one machine.
+ */
+pub fn run(address: &str) -> io::Result<()>
+{
+    let stream = TcpStream::connect(address)?;
+    stream.set_nodelay(true)?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    let line = read_line(&mut reader)?;
+    let count: usize = match line.split_whitespace().collect::<Vec<&str>>()[..] {
+        ["ARGS", count] => parse(count, &line)?,
+        _ => return Err(invalid(&line))
+    };
+    let args = (0..count).map(|_| read_line(&mut reader)).collect::<io::Result<Vec<String>>>()?;
+    let options = Options::parse(args);
+    let scene = crate::build_scene(&options);
+    eprintln!("Rendering for {}", address);
+
+    // Samples are spread into the pixels around a tile, which are sent along with it
+    let mut frame = Frame::new(IMAGE_WIDTH as usize, IMAGE_HEIGHT as usize, options.filter);
+    loop
+    {
+        let line = match read_line(&mut reader) {
+            Ok(line) => line,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e)
+        };
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (number, time, index, tile) = match fields[..] {
+            ["DONE"] => return Ok(()),
+            ["TILE", number, time, index, x, y, width, height] => {
+                let tile = Region { x: parse(x, &line)?, y: parse(y, &line)?, width: parse(width, &line)?, height: parse(height, &line)? };
+                (parse::<u32>(number, &line)?, parse::<f32>(time, &line)?, parse::<usize>(index, &line)?, tile)
+            }
+            _ => return Err(invalid(&line))
+        };
+        if tile.x + tile.width > IMAGE_WIDTH as usize || tile.y + tile.height > IMAGE_HEIGHT as usize
+        {
+            return Err(invalid(&line));
+        }
+
+        seed_random(crate::tile_seed(options.seed, number, index));
+        crate::render_tile(crate::camera_at(&scene, time).as_ref(), &scene, time, &options, &mut frame, &tile, SAMPLES_PER_PIXEL);
+
+        let area = tile.grown(options.filter.margin(), IMAGE_WIDTH as usize, IMAGE_HEIGHT as usize);
+        let values = frame.take(&area);
+        write_line(&mut writer, &format!("RESULT {} {} {} {} {} {} {}", number, index, area.x, area.y, area.width, area.height, values.len()))?;
+        write_values(&mut writer, &values)?;
+    }
+}
@@ -0,0 +1,22 @@
+use crate::vectors::{ray::Ray, vec3::{Color, Vec3}};
+
+/**
+ * Light arriving from infinitely far away, seen where rays leave the scene
+ */
+pub trait Environment
+{
+    /**
+     * Returns the light arriving along the ray, from the direction it travels in
+     */
+    fn emitted(&self, r: &Ray) -> Color;
+
+    /**
+     * Returns the solid angle pdf of random() choosing the direction
+     */
+    fn pdf_value(&self, direction: &Vec3) -> f32;
+
+    /**
+     * Returns a random direction, chosen roughly in proportion to the light from it
+     */
+    fn random(&self) -> Vec3;
+}
@@ -0,0 +1,167 @@
+use std::io;
+
+use crate::{vectors::{ray::Ray, spectrum::sample_color, vec3::{Color, Vec3, rotate_y}}, utility::{image::Image, rtweekend::{random_number, PI}}};
+
+use super::environment::Environment;
+
+/**
+ * Light from an equirectangular image around the scene, with +y up in the middle of the top row and
+ * -z in the center of the image. Directions are importance sampled by the luminance of the pixels.
+ */
+pub struct EnvironmentMap
+{
+    image: Image,
+    rotation: f32, // Radians around the y axis
+    intensity: f32,
+    row_cdf: Vec<f32>, // Cumulative probability of the rows, from the top
+    column_cdfs: Vec<Vec<f32>>, // Cumulative probability of the pixels within each row
+    pixel_pdf: Vec<f32> // Probability of picking each pixel
+}
+
+impl EnvironmentMap
+{
+    /**
+     * Creates a map from the image, turned by rotation degrees around the y axis and scaled by intensity
+     */
+    pub fn new(image: Image, rotation: f32, intensity: f32) -> EnvironmentMap
+    {
+        let (width, height) = (image.width(), image.height());
+
+        // Rows near the poles cover a smaller solid angle, so they are weighted by sin(theta)
+        let mut pixel_pdf = Vec::with_capacity(width * height);
+        for y in 0..height
+        {
+            let sin_theta = (PI as f32 * (y as f32 + 0.5) / height as f32).sin();
+            for x in 0..width
+            {
+                pixel_pdf.push(luminance(&image.pixel(x, y)).max(0.0) * sin_theta);
+            }
+        }
+        let total: f32 = pixel_pdf.iter().sum();
+        if total > 0.0
+        {
+            pixel_pdf.iter_mut().for_each(|p| *p /= total);
+        } else {
+            // A black map, sample it uniformly
+            pixel_pdf.iter_mut().for_each(|p| *p = 1.0 / (width * height) as f32);
+        }
+
+        let mut row_cdf = Vec::with_capacity(height);
+        let mut column_cdfs = Vec::with_capacity(height);
+        let mut sum = 0.0;
+        for row in pixel_pdf.chunks(width)
+        {
+            let row_total: f32 = row.iter().sum();
+            let mut row_sum = 0.0;
+            column_cdfs.push(row.iter().map(|p| {
+                row_sum += p;
+                if row_total > 0.0 { row_sum / row_total } else { 0.0 }
+            }).collect());
+            sum += row_total;
+            row_cdf.push(sum);
+        }
+
+        EnvironmentMap
+        {
+            image,
+            rotation: rotation.to_radians(),
+            intensity,
+            row_cdf,
+            column_cdfs,
+            pixel_pdf
+        }
+    }
+
+    /**
+     * Loads the map from a Radiance .hdr file, or a PPM/PGM image for other extensions
+     */
+    pub fn load(path: &str, rotation: f32, intensity: f32) -> io::Result<EnvironmentMap>
+    {
+        let image = if path.to_lowercase().ends_with(".hdr") {
+            Image::load_hdr(path)?
+        } else {
+            Image::load(path)?
+        };
+        Ok(EnvironmentMap::new(image, rotation, intensity))
+    }
+
+    /**
+     * Returns the image coordinates (u,v) in [0,1] for the direction, v going up
+     */
+    fn direction_to_uv(&self, direction: &Vec3) -> (f32, f32)
+    {
+        let d = rotate_y(&direction.unit_vector(), -self.rotation);
+        let u = 0.5 + d.x().atan2(-d.z()) / (2.0 * PI as f32);
+        let v = 0.5 + d.y().clamp(-1.0, 1.0).asin() / PI as f32;
+        (u, v)
+    }
+
+    fn uv_to_direction(&self, u: f32, v: f32) -> Vec3
+    {
+        let phi = (u - 0.5) * 2.0 * PI as f32;
+        let elevation = (v - 0.5) * PI as f32;
+        let d = Vec3::new(elevation.cos() * phi.sin(), elevation.sin(), -elevation.cos() * phi.cos());
+        rotate_y(&d, self.rotation)
+    }
+
+    /**
+     * Returns the pixel column and row that (u,v) falls in
+     */
+    fn pixel_at(&self, u: f32, v: f32) -> (usize, usize)
+    {
+        let x = ((u * self.image.width() as f32) as usize).min(self.image.width() - 1);
+        let y = (((1.0 - v) * self.image.height() as f32) as usize).min(self.image.height() - 1);
+        (x, y)
+    }
+}
+
+impl Environment for EnvironmentMap
+{
+    fn emitted(&self, r: &Ray) -> Color
+    {
+        let (u, v) = self.direction_to_uv(&r.direction());
+        let (x, y) = self.pixel_at(u, v);
+        sample_color(&self.image.pixel(x, y).const_mul(self.intensity), r)
+    }
+
+    fn pdf_value(&self, direction: &Vec3) -> f32
+    {
+        let (u, v) = self.direction_to_uv(direction);
+        let (x, y) = self.pixel_at(u, v);
+        let cos_elevation = ((v - 0.5) * PI as f32).cos();
+        if cos_elevation <= 0.0
+        {
+            return 0.0;
+        }
+
+        // From the density over the image to the density over solid angle
+        let image_pdf = self.pixel_pdf[x + y * self.image.width()] * (self.image.width() * self.image.height()) as f32;
+        image_pdf / (2.0 * PI as f32 * PI as f32 * cos_elevation)
+    }
+
+    fn random(&self) -> Vec3
+    {
+        let y = sample_cdf(&self.row_cdf, random_number() * self.row_cdf[self.row_cdf.len() - 1]);
+        let x = sample_cdf(&self.column_cdfs[y], random_number());
+
+        let u = (x as f32 + random_number()) / self.image.width() as f32;
+        let v = 1.0 - (y as f32 + random_number()) / self.image.height() as f32;
+        self.uv_to_direction(u, v)
+    }
+}
+
+/**
+ * Returns the first index where the cumulative distribution reaches the value
+ */
+pub fn sample_cdf(cdf: &[f32], value: f32) -> usize
+{
+    cdf.partition_point(|&c| c < value).min(cdf.len() - 1)
+}
+
+/**
+ * Relative luminance of a linear RGB color
+ */
+pub fn luminance(c: &Color) -> f32
+{
+    0.2126 * c.x() + 0.7152 * c.y() + 0.0722 * c.z()
+}
@@ -0,0 +1,3 @@
+pub mod environment;
+pub mod environment_map;
+pub mod sky;
\ No newline at end of file
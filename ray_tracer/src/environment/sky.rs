@@ -0,0 +1,223 @@
+use crate::{vectors::{ray::Ray, onb::Onb, spectrum::{sample_color, xyz_to_linear_srgb}, vec3::{Color, Vec3, dot, random_unit_vector}, color::RGB_WAVELENGTHS}, utility::rtweekend::{random_number, PI}};
+
+use super::environment::Environment;
+
+// Sky luminance is given in kcd/m^2 by the model, this brings it to the range of the other lights
+const SKY_SCALE: f32 = 0.05;
+// Illuminance from the sun above the atmosphere, in klx
+const SUN_ILLUMINANCE: f32 = 128.0;
+// Angular diameter of the sun in degrees
+const SUN_SIZE: f32 = 0.53;
+// Chance of sampling the sun instead of the whole sky, while the sun is up
+const SUN_PROBABILITY: f32 = 0.5;
+// Steps in elevation for finding the light falling on the ground, twice as many are taken around
+const GROUND_STEPS: usize = 32;
+
+/**
+ * Daylight from the Preetham sky model, with the sun as a bright disk. The sky is set by the position
+ * of the sun and the turbidity of the air, from 2 for a clear day to 10 for a hazy one. Below the horizon
+ * is a flat ground of the given albedo, lit by the sun and sky.
+ */
+pub struct Sky
+{
+    sun_direction: Vec3,
+    turbidity: f32,
+    perez: [[f32; 5]; 3], // Distribution coefficients for luminance Y and chromaticity x and y
+    zenith: Vec3, // Y, x and y straight up
+    sun_radiance: Color,
+    cos_sun_radius: f32,
+    sun_solid_angle: f32,
+    ground: Color
+}
+
+impl Sky
+{
+    /**
+     * Creates a sky with the sun at elevation degrees above the horizon and azimuth degrees around from -z towards +x
+     */
+    pub fn new(elevation: f32, azimuth: f32, turbidity: f32, ground_albedo: Color) -> Sky
+    {
+        let t = turbidity.clamp(1.7, 10.0);
+        let (elevation, azimuth) = (elevation.to_radians(), azimuth.to_radians());
+        let sun_direction = Vec3::new(elevation.cos() * azimuth.sin(), elevation.sin(), -elevation.cos() * azimuth.cos());
+
+        // The model does not cover the sun below the horizon, there the sky stays as at sunset
+        let theta_s = (PI as f32 / 2.0 - elevation).clamp(0.0, PI as f32 / 2.0);
+        let perez = [
+            [0.1787 * t - 1.4630, -0.3554 * t + 0.4275, -0.0227 * t + 5.3251, 0.1206 * t - 2.5771, -0.0670 * t + 0.3703],
+            [-0.0193 * t - 0.2592, -0.0665 * t + 0.0008, -0.0004 * t + 0.2125, -0.0641 * t - 0.8989, -0.0033 * t + 0.0452],
+            [-0.0167 * t - 0.2608, -0.0950 * t + 0.0092, -0.0079 * t + 0.2102, -0.0441 * t - 1.6537, -0.0109 * t + 0.0529]
+        ];
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI as f32 - 2.0 * theta_s);
+        let zenith_luminance = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+        let chromaticity = |c: [[f32; 4]; 3]| {
+            let poly = |k: [f32; 4]| k[0] * theta_s.powi(3) + k[1] * theta_s.powi(2) + k[2] * theta_s + k[3];
+            t * t * poly(c[0]) + t * poly(c[1]) + poly(c[2])
+        };
+        let zenith_x = chromaticity([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886]]);
+        let zenith_y = chromaticity([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688]]);
+
+        let mut sky = Sky
+        {
+            sun_direction,
+            turbidity: t,
+            perez,
+            zenith: Vec3::new(zenith_luminance.max(0.0), zenith_x, zenith_y),
+            sun_radiance: Color::new(0.0,0.0,0.0),
+            cos_sun_radius: 1.0,
+            sun_solid_angle: 0.0,
+            ground: Color::new(0.0,0.0,0.0)
+        };
+        sky.set_sun_size(SUN_SIZE);
+
+        // The ground reflects the light from the sky and sun diffusely
+        let mut irradiance = Color::new(0.0,0.0,0.0);
+        let (d_theta, d_phi) = (0.5 * PI as f32 / GROUND_STEPS as f32, PI as f32 / GROUND_STEPS as f32);
+        for i in 0..GROUND_STEPS
+        {
+            let theta = (i as f32 + 0.5) * d_theta;
+            for j in 0..2 * GROUND_STEPS
+            {
+                let phi = (j as f32 + 0.5) * d_phi;
+                let direction = Vec3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+                irradiance = irradiance + sky.sky_radiance(&direction).const_mul(theta.cos() * theta.sin() * d_theta * d_phi);
+            }
+        }
+        if sun_direction.y() > 0.0
+        {
+            irradiance = irradiance + sky.sun_transmittance().const_mul(SKY_SCALE * SUN_ILLUMINANCE * sun_direction.y());
+        }
+        sky.ground = ground_albedo * irradiance.const_div(PI as f32);
+
+        sky
+    }
+
+    /**
+     * Returns the sky with the sun drawn at the given angular diameter in degrees, for softer shadows.
+     * The sun gives the same light at any size.
+     */
+    pub fn with_sun_size(mut self, size: f32) -> Sky
+    {
+        self.set_sun_size(size);
+        self
+    }
+
+    fn set_sun_size(&mut self, size: f32)
+    {
+        // 1 - cos(radius) is written with the half angle, to stay accurate for a small sun
+        let radius = (0.5 * size.clamp(0.01, 90.0)).to_radians();
+        self.cos_sun_radius = radius.cos();
+        self.sun_solid_angle = 4.0 * PI as f32 * (0.5 * radius).sin().powi(2);
+        self.sun_radiance = if self.sun_direction.y() > 0.0 {
+            self.sun_transmittance().const_mul(SKY_SCALE * SUN_ILLUMINANCE / self.sun_solid_angle)
+        } else {
+            Color::new(0.0,0.0,0.0)
+        };
+    }
+
+    /**
+     * Fraction of the sunlight of each channel that makes it through the atmosphere, from Rayleigh
+     * scattering by the air and Mie scattering by haze
+     */
+    fn sun_transmittance(&self) -> Color
+    {
+        let theta_s = self.sun_direction.y().clamp(0.0, 1.0).acos();
+        let air_mass = 1.0 / (theta_s.cos() + 0.15 * (93.885 - theta_s.to_degrees()).powf(-1.253));
+        let beta = 0.04608 * self.turbidity - 0.04586;
+
+        let channel = |lambda: f32| {
+            let lambda = lambda / 1000.0; // In micrometers
+            let rayleigh = -0.008735 * lambda.powf(-4.08) * air_mass;
+            let aerosol = -beta * lambda.powf(-1.3) * air_mass;
+            (rayleigh + aerosol).exp()
+        };
+        Color::new(channel(RGB_WAVELENGTHS[0]), channel(RGB_WAVELENGTHS[1]), channel(RGB_WAVELENGTHS[2]))
+    }
+
+    /**
+     * Light from the sky itself in an upwards direction, without the sun
+     */
+    fn sky_radiance(&self, direction: &Vec3) -> Color
+    {
+        let cos_theta = direction.y().max(0.001);
+        let cos_gamma = dot(direction, &self.sun_direction).clamp(-1.0, 1.0);
+        let cos_theta_s = self.sun_direction.y().clamp(0.0, 1.0);
+
+        // Each quantity is its zenith value scaled by the Perez distribution relative to the zenith
+        let distribute = |k: &[f32; 5], zenith: f32| {
+            let perez = |cos_theta: f32, cos_gamma: f32| {
+                let gamma = cos_gamma.acos();
+                (1.0 + k[0] * (k[1] / cos_theta).exp()) * (1.0 + k[2] * (k[3] * gamma).exp() + k[4] * cos_gamma * cos_gamma)
+            };
+            zenith * perez(cos_theta, cos_gamma) / perez(1.0, cos_theta_s)
+        };
+        let luminance = distribute(&self.perez[0], self.zenith.x()) * SKY_SCALE;
+        let x = distribute(&self.perez[1], self.zenith.y());
+        let y = distribute(&self.perez[2], self.zenith.z());
+        if y <= 0.0 || luminance <= 0.0
+        {
+            return Color::new(0.0,0.0,0.0);
+        }
+
+        let xyz = Vec3::new(x / y * luminance, luminance, (1.0 - x - y) / y * luminance);
+        let rgb = xyz_to_linear_srgb(&xyz);
+        Color::new(rgb.x().max(0.0), rgb.y().max(0.0), rgb.z().max(0.0))
+    }
+
+    fn sun_probability(&self) -> f32
+    {
+        if self.sun_direction.y() > 0.0 { SUN_PROBABILITY } else { 0.0 }
+    }
+}
+
+impl Environment for Sky
+{
+    fn emitted(&self, r: &Ray) -> Color
+    {
+        let direction = r.direction().unit_vector();
+        if direction.y() < 0.0
+        {
+            return sample_color(&self.ground, r);
+        }
+
+        let mut light = self.sky_radiance(&direction);
+        if dot(&direction, &self.sun_direction) >= self.cos_sun_radius
+        {
+            light = light + self.sun_radiance;
+        }
+        sample_color(&light, r)
+    }
+
+    fn pdf_value(&self, direction: &Vec3) -> f32
+    {
+        let sun_probability = self.sun_probability();
+        let mut pdf = (1.0 - sun_probability) / (4.0 * PI as f32);
+        if dot(&direction.unit_vector(), &self.sun_direction) >= self.cos_sun_radius
+        {
+            pdf += sun_probability / self.sun_solid_angle;
+        }
+        pdf
+    }
+
+    fn random(&self) -> Vec3
+    {
+        if random_number() >= self.sun_probability()
+        {
+            return random_unit_vector();
+        }
+
+        // A direction within the cone of the sun
+        let uvw = Onb::new(&self.sun_direction);
+        let z = 1.0 - random_number() * self.sun_solid_angle / (2.0 * PI as f32);
+        let phi = 2.0 * PI as f32 * random_number();
+        let sin_theta = (1.0 - z*z).max(0.0).sqrt();
+        uvw.local(&Vec3::new(phi.cos()*sin_theta, phi.sin()*sin_theta, z))
+    }
+}
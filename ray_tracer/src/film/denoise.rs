@@ -0,0 +1,94 @@
+use crate::{vectors::vec3::{Color, dot}, utility::image::Image};
+
+// Passes of the filter, each reaching twice as far as the last
+const ITERATIONS: i32 = 5;
+// B3 spline weights of the 5x5 kernel
+const KERNEL: [f32; 5] = [1.0/16.0, 1.0/4.0, 3.0/8.0, 1.0/4.0, 1.0/16.0];
+// How quickly the weight of a neighbour falls off with differences in light, normal and albedo.
+// The light is compared after tone mapping, and its tolerance is halved with each pass as the noise goes down.
+const SIGMA_COLOR: f32 = 0.25;
+const SIGMA_NORMAL: f32 = 0.3;
+const SIGMA_ALBEDO: f32 = 0.1;
+// Albedo below which light is not divided by it, to keep dark surfaces from blowing up
+const MIN_ALBEDO: f32 = 0.01;
+
+/**
+ * Removes noise from an image with the edge-avoiding a-trous wavelet filter of Dammertz et al., guided by
+ * the albedo and normal buffers. The light falling on the surfaces is filtered separately from their albedo,
+ * which is multiplied back in afterwards, so textures stay sharp.
+ */
+pub fn denoise(color: &Image, albedo: &Image, normal: &Image) -> Image
+{
+    let (width, height) = (color.width(), color.height());
+    let (albedo, normal) = (albedo.pixels(), normal.pixels());
+    let divisor: Vec<Color> = albedo.iter().map(|a| {
+        Color::new(a.x().max(MIN_ALBEDO), a.y().max(MIN_ALBEDO), a.z().max(MIN_ALBEDO))
+    }).collect();
+
+    let mut light: Vec<Color> = color.pixels().iter().zip(&divisor).map(|(c, a)| {
+        Color::new(c.x() / a.x(), c.y() / a.y(), c.z() / a.z())
+    }).collect();
+    for iteration in 0..ITERATIONS
+    {
+        let step = 1 << iteration;
+        let sigma_color = SIGMA_COLOR * SIGMA_COLOR / (1 << iteration) as f32;
+        let tone_mapped: Vec<Color> = light.iter().map(tone_map).collect();
+
+        let mut filtered = Vec::with_capacity(light.len());
+        for y in 0..height as i32
+        {
+            for x in 0..width as i32
+            {
+                let p = (x + y * width as i32) as usize;
+                let mut sum = Color::new(0.0,0.0,0.0);
+                let mut total_weight = 0.0;
+
+                for (j, ky) in KERNEL.iter().enumerate()
+                {
+                    let qy = y + (j as i32 - 2) * step;
+                    if qy < 0 || qy >= height as i32
+                    {
+                        continue;
+                    }
+                    for (i, kx) in KERNEL.iter().enumerate()
+                    {
+                        let qx = x + (i as i32 - 2) * step;
+                        if qx < 0 || qx >= width as i32
+                        {
+                            continue;
+                        }
+                        let q = (qx + qy * width as i32) as usize;
+
+                        let color_distance = distance_squared(&tone_mapped[p], &tone_mapped[q]);
+                        let normal_distance = distance_squared(&normal[p], &normal[q]);
+                        let albedo_distance = distance_squared(&albedo[p], &albedo[q]);
+                        let weight = kx * ky * (-color_distance / sigma_color
+                            - normal_distance / (SIGMA_NORMAL * SIGMA_NORMAL)
+                            - albedo_distance / (SIGMA_ALBEDO * SIGMA_ALBEDO)).exp();
+
+                        sum = sum + light[q].const_mul(weight);
+                        total_weight += weight;
+                    }
+                }
+                filtered.push(sum.const_div(total_weight));
+            }
+        }
+        light = filtered;
+    }
+
+    Image::new(width, height, light.iter().zip(&divisor).map(|(l, a)| *l * *a).collect())
+}
+
+/**
+ * Compresses bright values into [0,1), so differences are judged as they look
+ */
+fn tone_map(c: &Color) -> Color
+{
+    Color::new(c.x() / (1.0 + c.x()), c.y() / (1.0 + c.y()), c.z() / (1.0 + c.z()))
+}
+
+fn distance_squared(a: &Color, b: &Color) -> f32
+{
+    let d = *a - *b;
+    dot(&d, &d)
+}
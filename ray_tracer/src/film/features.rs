@@ -0,0 +1,17 @@
+use crate::vectors::vec3::{Color, Vec3};
+
+/**
+ * What a camera ray sees. The color of the surface and its normal are taken past any mirrors and glass,
+ * these are smooth where the image is only noisy, so they show the denoiser where the real edges are.
+ * The distance, material and object are those of the first hit.
+ */
+#[derive(Copy, Clone, Default)]
+pub struct Features
+{
+    pub albedo: Color,
+    pub normal: Vec3, // Zero where the ray left the scene
+    pub depth: f32, // Distance along the ray to the first hit
+    pub material: usize, // Number of the material first hit
+    pub object_id: Option<usize>, // Index of the object first hit in the world, None where nothing was hit
+    pub bounces: i32 // Times the path scattered before it ended
+}
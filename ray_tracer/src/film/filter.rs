@@ -0,0 +1,164 @@
+use crate::utility::rtweekend::PI;
+
+/**
+ * Reconstruction filters, which give the weight of a sample in a pixel by its distance from the pixel center.
+ * They are separable, the weight of a sample is the filter across times the filter down.
+ */
+#[derive(Copy, Clone)]
+pub enum Filter
+{
+    Box, // Average of the samples in each pixel
+    Tent, // Falls off linearly over one pixel
+    Gaussian, // Smooth, a little soft
+    Mitchell, // Mitchell-Netravali cubic with B = C = 1/3, sharp with little ringing
+    Lanczos // Windowed sinc, sharpest but rings around hard edges
+}
+
+impl Filter
+{
+    pub fn from_name(name: &str) -> Option<Filter>
+    {
+        match name
+        {
+            "box" => Some(Filter::Box),
+            "tent" => Some(Filter::Tent),
+            "gaussian" => Some(Filter::Gaussian),
+            "mitchell" => Some(Filter::Mitchell),
+            "lanczos" => Some(Filter::Lanczos),
+            _ => None
+        }
+    }
+
+    /**
+     * Distance in pixels beyond which the filter is zero
+     */
+    pub fn radius(&self) -> f32
+    {
+        match self
+        {
+            Filter::Box => 0.5,
+            Filter::Tent => 1.0,
+            Filter::Gaussian => 1.5,
+            Filter::Mitchell => 2.0,
+            Filter::Lanczos => 3.0
+        }
+    }
+
+    /**
+     * Pixels around a pixel whose samples the filter spreads into it
+     */
+    pub fn margin(&self) -> usize
+    {
+        (self.radius() - 0.5).ceil() as usize
+    }
+
+    /**
+     * Returns the filter at distance x in pixels, which may be negative for filters that sharpen
+     */
+    pub fn evaluate(&self, x: f32) -> f32
+    {
+        let x = x.abs();
+        let radius = self.radius();
+        if x >= radius
+        {
+            return 0.0;
+        }
+
+        match self
+        {
+            Filter::Box => 1.0,
+            Filter::Tent => radius - x,
+            Filter::Gaussian => {
+                // Shifted down to reach zero at the radius
+                let alpha = 2.0;
+                (-alpha * x * x).exp() - (-alpha * radius * radius).exp()
+            }
+            Filter::Mitchell => {
+                let (b, c) = (1.0 / 3.0, 1.0 / 3.0);
+                // The cubic is defined over [-2,2]
+                let x = 2.0 * x / radius;
+                let value = if x < 1.0 {
+                    (12.0 - 9.0*b - 6.0*c) * x*x*x + (-18.0 + 12.0*b + 6.0*c) * x*x + (6.0 - 2.0*b)
+                } else {
+                    (-b - 6.0*c) * x*x*x + (6.0*b + 30.0*c) * x*x + (-12.0*b - 48.0*c) * x + (8.0*b + 24.0*c)
+                };
+                value / 6.0
+            }
+            Filter::Lanczos => sinc(x) * sinc(x / radius)
+        }
+    }
+}
+
+/**
+ * Normalised sinc, sin(pi x) / (pi x)
+ */
+fn sinc(x: f32) -> f32
+{
+    if x < 1e-5
+    {
+        return 1.0;
+    }
+    let pi_x = PI as f32 * x;
+    pi_x.sin() / pi_x
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const FILTERS: [Filter; 5] = [Filter::Box, Filter::Tent, Filter::Gaussian, Filter::Mitchell, Filter::Lanczos];
+
+    #[test]
+    fn zero_from_the_radius_on_and_symmetric()
+    {
+        for filter in FILTERS
+        {
+            let radius = filter.radius();
+            assert_eq!(filter.evaluate(radius), 0.0);
+            assert_eq!(filter.evaluate(-radius - 0.1), 0.0);
+            for x in [0.0, 0.2, 0.7, 1.3]
+            {
+                assert_eq!(filter.evaluate(x), filter.evaluate(-x));
+            }
+        }
+    }
+
+    #[test]
+    fn known_values()
+    {
+        assert_eq!(Filter::Box.evaluate(0.49), 1.0);
+        assert!((Filter::Tent.evaluate(0.0) - 1.0).abs() < 1e-6);
+        assert!((Filter::Tent.evaluate(0.25) - 0.75).abs() < 1e-6);
+        assert!((Filter::Mitchell.evaluate(0.0) - 8.0 / 9.0).abs() < 1e-6);
+        assert!((Filter::Mitchell.evaluate(1.0) - 1.0 / 18.0).abs() < 1e-6);
+        assert!((Filter::Lanczos.evaluate(0.0) - 1.0).abs() < 1e-6);
+        assert!(Filter::Lanczos.evaluate(1.0).abs() < 1e-6);
+        assert!(Filter::Lanczos.evaluate(2.0).abs() < 1e-6);
+        assert!(Filter::Gaussian.evaluate(0.0) > Filter::Gaussian.evaluate(1.0));
+        assert!(Filter::Gaussian.evaluate(1.4) > 0.0);
+    }
+
+    #[test]
+    fn mitchell_weights_sum_to_one()
+    {
+        // Samples spread into the pixels around them with weights that add up to one, wherever they are
+        for offset in [0.0, 0.25, 0.5, 0.8]
+        {
+            let sum: f32 = (-3..=3).map(|k| Filter::Mitchell.evaluate(offset + k as f32)).sum();
+            assert!((sum - 1.0).abs() < 1e-5, "{} at offset {}", sum, offset);
+        }
+    }
+
+    #[test]
+    fn margins_and_names()
+    {
+        let margins: Vec<usize> = FILTERS.iter().map(|filter| filter.margin()).collect();
+        assert_eq!(margins, vec![0, 1, 1, 2, 3]);
+        for name in ["box", "tent", "gaussian", "mitchell", "lanczos"]
+        {
+            assert!(Filter::from_name(name).is_some());
+        }
+        assert!(Filter::from_name("sinc").is_none());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{vectors::vec3::Color, utility::{image::Image, rtweekend::INFINITY}};
+
+use super::{features::Features, filter::Filter, region::Region};
+
+// Values sent for each pixel of a part of a frame: color, albedo, normal, weight, depth, hits, material and object id
+pub const PIXEL_VALUES: usize = 14;
+
+/**
+ * Buffers that collect the samples of an image, along with the features seen through each pixel.
+ * Each sample is spread over the pixels around it by the reconstruction filter, except for the depth and
+ * ids which only go to the pixel the sample is in. Pixels are counted from the top left corner.
+ */
+pub struct Frame
+{
+    width: usize,
+    height: usize,
+    filter: Filter,
+    color: Vec<Color>,
+    albedo: Vec<Color>,
+    normal: Vec<Color>,
+    weight: Vec<f32>, // Sum of the filter weights
+    depth: Vec<f32>, // Sum over the samples that hit something
+    hits: Vec<f32>,
+    material_id: Vec<Option<usize>>, // Of the first sample in the pixel to hit something
+    object_id: Vec<Option<usize>>
+}
+
+impl Frame
+{
+    pub fn new(width: usize, height: usize, filter: Filter) -> Frame
+    {
+        let black = vec![Color::new(0.0,0.0,0.0); width * height];
+
+        Frame
+        {
+            width,
+            height,
+            filter,
+            color: black.clone(),
+            albedo: black.clone(),
+            normal: black,
+            weight: vec![0.0; width * height],
+            depth: vec![0.0; width * height],
+            hits: vec![0.0; width * height],
+            material_id: vec![None; width * height],
+            object_id: vec![None; width * height]
+        }
+    }
+
+    /**
+     * Adds a sample of the light and features at (x,y) in pixels, where pixel centers are at half pixels
+     */
+    pub fn add_sample(&mut self, x: f32, y: f32, color: Color, features: &Features)
+    {
+        let radius = self.filter.radius();
+        let span = |position: f32, size: usize| {
+            let first = (position - 0.5 - radius).ceil().max(0.0) as usize;
+            let last = ((position - 0.5 + radius).floor().max(0.0) as usize).min(size - 1);
+            first..=last
+        };
+
+        for py in span(y, self.height)
+        {
+            let weight_y = self.filter.evaluate(py as f32 + 0.5 - y);
+            if weight_y == 0.0
+            {
+                continue;
+            }
+            for px in span(x, self.width)
+            {
+                let weight = weight_y * self.filter.evaluate(px as f32 + 0.5 - x);
+                if weight == 0.0
+                {
+                    continue;
+                }
+                let i = px + self.width * py;
+                self.color[i] = self.color[i] + color.const_mul(weight);
+                self.albedo[i] = self.albedo[i] + features.albedo.const_mul(weight);
+                self.normal[i] = self.normal[i] + features.normal.const_mul(weight);
+                self.weight[i] += weight;
+            }
+        }
+
+        let i = (x as usize).min(self.width - 1) + self.width * (y as usize).min(self.height - 1);
+        if let Some(object_id) = features.object_id
+        {
+            self.depth[i] += features.depth;
+            self.hits[i] += 1.0;
+            if self.object_id[i].is_none()
+            {
+                self.material_id[i] = Some(features.material);
+                self.object_id[i] = Some(object_id);
+            }
+        }
+    }
+
+    /**
+     * Takes the sums collected in part of the frame out as plain values, to add to another frame, and
+     * clears them
+     */
+    pub fn take(&mut self, area: &Region) -> Vec<f32>
+    {
+        let mut values = Vec::with_capacity(area.width * area.height * PIXEL_VALUES);
+        for y in area.y..area.y + area.height
+        {
+            for x in area.x..area.x + area.width
+            {
+                let i = x + self.width * y;
+                let material_id = self.material_id[i].map_or(-1.0, |id| id as f32);
+                let object_id = self.object_id[i].map_or(-1.0, |id| id as f32);
+                for color in [self.color[i], self.albedo[i], self.normal[i]]
+                {
+                    values.extend([color.x(), color.y(), color.z()]);
+                }
+                values.extend([self.weight[i], self.depth[i], self.hits[i], material_id, object_id]);
+
+                let black = Color::new(0.0,0.0,0.0);
+                self.color[i] = black;
+                self.albedo[i] = black;
+                self.normal[i] = black;
+                self.weight[i] = 0.0;
+                self.depth[i] = 0.0;
+                self.hits[i] = 0.0;
+                self.material_id[i] = None;
+                self.object_id[i] = None;
+            }
+        }
+        values
+    }
+
+    /**
+     * Adds values taken out of part of another frame. A pixel keeps the material and object it already has.
+     */
+    pub fn add(&mut self, area: &Region, values: &[f32])
+    {
+        let pixels = (area.y..area.y + area.height).flat_map(|y| (area.x..area.x + area.width).map(move |x| (x, y)));
+        for ((x, y), v) in pixels.zip(values.chunks(PIXEL_VALUES))
+        {
+            let i = x + self.width * y;
+            self.color[i] = self.color[i] + Color::new(v[0], v[1], v[2]);
+            self.albedo[i] = self.albedo[i] + Color::new(v[3], v[4], v[5]);
+            self.normal[i] = self.normal[i] + Color::new(v[6], v[7], v[8]);
+            self.weight[i] += v[9];
+            self.depth[i] += v[10];
+            self.hits[i] += v[11];
+            if self.object_id[i].is_none() && v[13] >= 0.0
+            {
+                self.material_id[i] = Some(v[12] as usize);
+                self.object_id[i] = Some(v[13] as usize);
+            }
+        }
+    }
+
+    /**
+     * Returns the average color of the samples in each pixel
+     */
+    pub fn color(&self) -> Image
+    {
+        self.resolve(&self.color)
+    }
+
+    pub fn albedo(&self) -> Image
+    {
+        self.resolve(&self.albedo)
+    }
+
+    /**
+     * Returns the average normal in each pixel, which is shorter than one where the pixel covers an edge
+     */
+    pub fn normal(&self) -> Image
+    {
+        self.resolve(&self.normal)
+    }
+
+    /**
+     * Returns the average distance to the first hit in each pixel, infinite where nothing was hit
+     */
+    pub fn depth(&self) -> Image
+    {
+        let pixels = self.depth.iter().zip(&self.hits).map(|(sum, hits)| {
+            let depth = if *hits > 0.0 { sum / hits } else { INFINITY };
+            Color::new(depth, depth, depth)
+        }).collect();
+        Image::new(self.width, self.height, pixels)
+    }
+
+    /**
+     * Returns the number of the material first hit in each pixel, counted from 1, or 0 where nothing was hit
+     */
+    pub fn material_id(&self) -> Image
+    {
+        Frame::ids(self.width, self.height, &self.material_id)
+    }
+
+    /**
+     * Returns the number of the object in the world first hit in each pixel, counted from 1, or 0 where nothing was hit
+     */
+    pub fn object_id(&self) -> Image
+    {
+        Frame::ids(self.width, self.height, &self.object_id)
+    }
+
+    fn ids(width: usize, height: usize, ids: &[Option<usize>]) -> Image
+    {
+        let pixels = ids.iter().map(|id| {
+            let value = id.map_or(0.0, |id| (id + 1) as f32);
+            Color::new(value, value, value)
+        }).collect();
+        Image::new(width, height, pixels)
+    }
+
+    fn resolve(&self, buffer: &[Color]) -> Image
+    {
+        // Filters with negative lobes can leave pixels with no weight at all, which stay black
+        let pixels = buffer.iter().zip(&self.weight).map(|(sum, weight)| {
+            if *weight > 0.0 { sum.const_div(*weight) } else { Color::new(0.0,0.0,0.0) }
+        }).collect();
+        Image::new(self.width, self.height, pixels)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod features;
+pub mod frame;
+pub mod denoise;
+pub mod filter;
+pub mod region;
\ No newline at end of file
@@ -0,0 +1,266 @@
+use std::{fs, io, io::Write};
+
+use crate::{vectors::vec3::Color, utility::image::Image};
+
+/**
+ * A rectangle of pixels within the full image, counted from its top left corner
+ */
+#[derive(Copy, Clone)]
+pub struct Region
+{
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize
+}
+
+impl Region
+{
+    /**
+     * Reads a region written as "x,y,width,height"
+     */
+    pub fn parse(value: &str) -> Option<Region>
+    {
+        let fields: Vec<usize> = value.split(',').map(|field| field.trim().parse::<usize>().ok()).collect::<Option<_>>()?;
+        match fields[..]
+        {
+            [x, y, width, height] if width > 0 && height > 0 => Some(Region { x, y, width, height }),
+            _ => None
+        }
+    }
+
+    /**
+     * Returns the part of the region inside an image of the given size, None if there is nothing left
+     */
+    pub fn clamped(&self, width: usize, height: usize) -> Option<Region>
+    {
+        if self.x >= width || self.y >= height
+        {
+            return None;
+        }
+
+        Some(Region
+        {
+            width: self.width.min(width - self.x),
+            height: self.height.min(height - self.y),
+            ..*self
+        })
+    }
+
+    /**
+     * Returns the region grown by margin pixels on every side, within an image of the given size
+     */
+    pub fn grown(&self, margin: usize, width: usize, height: usize) -> Region
+    {
+        let x = self.x.saturating_sub(margin);
+        let y = self.y.saturating_sub(margin);
+        Region
+        {
+            x,
+            y,
+            width: (self.x + self.width + margin).min(width) - x,
+            height: (self.y + self.height + margin).min(height) - y
+        }
+    }
+
+    /**
+     * Splits the region into tiles of at most size by size pixels, row by row from the top left
+     */
+    pub fn tiles(&self, size: usize) -> Vec<Region>
+    {
+        let mut tiles = Vec::new();
+        for y in (self.y..self.y + self.height).step_by(size)
+        {
+            for x in (self.x..self.x + self.width).step_by(size)
+            {
+                let width = size.min(self.x + self.width - x);
+                let height = size.min(self.y + self.height - y);
+                tiles.push(Region { x, y, width, height });
+            }
+        }
+        tiles
+    }
+
+    /**
+     * Returns true if the pixel is inside the region grown by margin pixels on every side
+     */
+    pub fn contains(&self, x: usize, y: usize, margin: usize) -> bool
+    {
+        x + margin >= self.x && x < self.x + self.width + margin && y + margin >= self.y && y < self.y + self.height + margin
+    }
+
+    /**
+     * Returns the pixels of the full image inside the region
+     */
+    pub fn crop(&self, image: &Image) -> Image
+    {
+        let pixels = (self.y..self.y + self.height)
+            .flat_map(|y| (self.x..self.x + self.width).map(move |x| (x, y)))
+            .map(|(x, y)| image.pixel(x, y))
+            .collect();
+        Image::new(self.width, self.height, pixels)
+    }
+
+    /**
+     * Places a cropped image back in a black image of the full size
+     */
+    pub fn uncrop(&self, image: &Image, width: usize, height: usize) -> Image
+    {
+        let pixels = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| {
+            if self.contains(x, y, 0) { image.pixel(x - self.x, y - self.y) } else { Color::new(0.0,0.0,0.0) }
+        }).collect();
+        Image::new(width, height, pixels)
+    }
+
+    /**
+     * Describes the region and the size of the full image, for the header of an image file
+     */
+    pub fn comment(&self, width: usize, height: usize) -> String
+    {
+        format!("region {} {} {} {} of {} {}", self.x, self.y, self.width, self.height, width, height)
+    }
+
+    /**
+     * Reads the region and full image size back from the header of an image file
+     */
+    fn from_comment(line: &str) -> Option<(Region, usize, usize)>
+    {
+        let fields: Vec<&str> = line.trim_start_matches('#').split_whitespace().collect();
+        let number = |i: usize| fields.get(i).and_then(|field| field.parse::<usize>().ok());
+        if fields.first() != Some(&"region") || fields.get(5) != Some(&"of")
+        {
+            return None;
+        }
+        let region = Region { x: number(1)?, y: number(2)?, width: number(3)?, height: number(4)? };
+        Some((region, number(6)?, number(7)?))
+    }
+}
+
+/**
+ * Puts region images back together into one image of the full size, written as a plain PPM. Each image is
+ * placed by the region in its header, and may be either cropped or of the full size. Pixels that are in no
+ * region stay black.
+ */
+pub fn merge(paths: &[String], handle: &mut dyn Write) -> io::Result<()>
+{
+    let invalid = |path: &str, message: &str| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path, message));
+
+    let mut merged: Option<(usize, usize, Vec<Color>)> = None;
+    for path in paths
+    {
+        let header = fs::read(path)?;
+        let (region, width, height) = String::from_utf8_lossy(&header)
+            .lines()
+            .take_while(|line| line.starts_with('#') || line.starts_with('P'))
+            .find_map(Region::from_comment)
+            .ok_or_else(|| invalid(path, "no region in the header"))?;
+        let image = Image::load(path)?;
+
+        let (full_width, full_height, pixels) = merged.get_or_insert_with(|| (width, height, vec![Color::new(0.0,0.0,0.0); width * height]));
+        if (*full_width, *full_height) != (width, height)
+        {
+            return Err(invalid(path, "region of an image of a different size"));
+        }
+        let region = region.clamped(width, height).ok_or_else(|| invalid(path, "region outside the image"))?;
+        let (offset_x, offset_y) = match (image.width(), image.height()) {
+            (w, h) if (w, h) == (width, height) => (region.x, region.y),
+            (w, h) if w >= region.width && h >= region.height => (0, 0),
+            _ => return Err(invalid(path, "image smaller than its region"))
+        };
+
+        for y in 0..region.height
+        {
+            for x in 0..region.width
+            {
+                pixels[region.x + x + width * (region.y + y)] = image.pixel(offset_x + x, offset_y + y);
+            }
+        }
+    }
+
+    // The images are already gamma corrected, so the values are written back as they were read, as whole numbers
+    let (width, height, pixels) = merged.ok_or_else(|| invalid("merge", "no images"))?;
+    handle.write_all(format!("P3\n{} {}\n255\n", width, height).as_bytes())?;
+    let channel = |value: f32| (value * 255.0).round().clamp(0.0, 255.0) as u8;
+    for pixel in pixels
+    {
+        handle.write_all(format!("{} {} {}\n", channel(pixel.x()), channel(pixel.y()), channel(pixel.z())).as_bytes())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::{env, fs};
+
+    use super::*;
+
+    #[test]
+    fn parse_region()
+    {
+        let region = Region::parse("3, 4,10,20").unwrap();
+        assert_eq!((region.x, region.y, region.width, region.height), (3, 4, 10, 20));
+        assert!(Region::parse("3,4,0,20").is_none());
+        assert!(Region::parse("3,4,10").is_none());
+        assert!(Region::parse("3,4,10,20,5").is_none());
+        assert!(Region::parse("3,-4,10,20").is_none());
+        assert!(Region::parse("").is_none());
+    }
+
+    #[test]
+    fn comment_round_trip()
+    {
+        let region = Region { x: 1, y: 2, width: 3, height: 4 };
+        let (read, width, height) = Region::from_comment(&format!("# {}", region.comment(640, 480))).unwrap();
+        assert_eq!((read.x, read.y, read.width, read.height, width, height), (1, 2, 3, 4, 640, 480));
+        assert!(Region::from_comment("# made by hand").is_none());
+    }
+
+    #[test]
+    fn clamp_and_tile()
+    {
+        let region = Region { x: 6, y: 2, width: 10, height: 10 }.clamped(8, 8).unwrap();
+        assert_eq!((region.width, region.height), (2, 6));
+        assert!(Region { x: 8, y: 0, width: 1, height: 1 }.clamped(8, 8).is_none());
+
+        let tiles = Region { x: 0, y: 0, width: 5, height: 3 }.tiles(2);
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(tiles.iter().map(|tile| tile.width * tile.height).sum::<usize>(), 15);
+    }
+
+    #[test]
+    fn merge_cropped_and_full_regions()
+    {
+        let dir = env::temp_dir();
+        let cropped = dir.join(format!("region_test_{}_cropped.ppm", std::process::id()));
+        let full = dir.join(format!("region_test_{}_full.ppm", std::process::id()));
+        // The left column as a cropped image, the right column inside a full sized image
+        fs::write(&cropped, "P3\n# region 0 0 1 2 of 3 2\n1 2\n255\n255 0 0\n0 255 0\n").unwrap();
+        fs::write(&full, "P3\n# region 2 0 1 2 of 3 2\n3 2\n255\n9 9 9 9 9 9 0 0 255\n9 9 9 9 9 9 10 20 30\n").unwrap();
+
+        let mut output = Vec::new();
+        let paths = [cropped.to_string_lossy().into_owned(), full.to_string_lossy().into_owned()];
+        let result = merge(&paths, &mut output);
+        fs::remove_file(&cropped).unwrap();
+        fs::remove_file(&full).unwrap();
+        result.unwrap();
+
+        let expected = "P3\n3 2\n255\n255 0 0\n0 0 0\n0 0 255\n0 255 0\n0 0 0\n10 20 30\n";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn merge_rejects_different_sizes()
+    {
+        let path = env::temp_dir().join(format!("region_test_{}_size.ppm", std::process::id()));
+        fs::write(&path, "P3\n# region 0 0 1 1 of 2 2\n1 1\n255\n1 2 3\n").unwrap();
+        let other = env::temp_dir().join(format!("region_test_{}_other.ppm", std::process::id()));
+        fs::write(&other, "P3\n# region 0 0 1 1 of 3 3\n1 1\n255\n1 2 3\n").unwrap();
+
+        let paths = [path.to_string_lossy().into_owned(), other.to_string_lossy().into_owned()];
+        let result = merge(&paths, &mut Vec::new());
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&other).unwrap();
+        assert!(result.is_err());
+    }
+}
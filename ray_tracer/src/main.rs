@@ -2,23 +2,67 @@ mod vectors;
 mod objects;
 mod camera;
 mod utility;
+mod scene;
+mod pdf;
+mod options;
+mod environment;
+mod film;
+mod distributed;
+mod preview;
 
 use objects::material;
 use objects::material::material::Material;
 use utility::rtweekend::random_number_custom;
 use vectors::vec3::{Point3, random_vec, random_vec_custom};
 
-use crate::camera::Camera;
+use crate::camera::aperture::{Aperture, Mask};
+use crate::camera::camera::Camera;
+use crate::camera::camera_animation::CameraAnimation;
+use crate::camera::equirectangular::Equirectangular;
+use crate::camera::fisheye::Fisheye;
+use crate::camera::orthographic::Orthographic;
+use crate::camera::perspective::Perspective;
+use crate::distributed::{coordinator::Coordinator, worker};
+use crate::environment::environment_map::EnvironmentMap;
+use crate::environment::sky::Sky;
+use crate::film::denoise::denoise;
+use crate::film::features::Features;
+use crate::film::frame::Frame;
+use crate::film::region::{merge, Region};
+use crate::preview::terminal::TerminalPreview;
+use crate::objects::animated::Animated;
+use crate::objects::cone::Cone;
+use crate::objects::cylinder::Cylinder;
+use crate::objects::disk::Disk;
+use crate::objects::torus::Torus;
+use crate::objects::plane::Plane;
+use crate::objects::quad::Quad;
+use crate::objects::csg::{Csg, Operation};
 use crate::objects::hittable_list::HittableList;
-use crate::objects::material::dielectric::Dielectric;
+use crate::objects::material::coated::Coated;
+use crate::objects::material::dielectric::{Dielectric, Dispersion};
 use crate::objects::material::lambertian::Lambertian;
 use crate::objects::material::metal::Metal;
+use crate::objects::medium::constant_density::ConstantDensity;
+use crate::objects::medium::grid_density::GridDensity;
+use crate::objects::medium::noise_density::NoiseDensity;
+use crate::objects::medium::subsurface::Subsurface;
+use crate::objects::medium::volume::Volume;
 use crate::objects::sphere::Sphere;
-use crate::utility::rtweekend::random_number;
+use crate::objects::material::diffuse_light::DiffuseLight;
+use crate::objects::material::principled::Principled;
+use crate::options::Options;
+use crate::scene::Scene;
+use crate::utility::animation::Track;
+use crate::utility::image::Image;
+use crate::utility::rtweekend::{mix_seed, random_number, seed_random, PI};
 use crate::vectors::vec3::{Color, Vec3};
 use crate::vectors::color::*;
+use crate::vectors::debug::debug_color;
+use crate::vectors::spectrum::{sample_wavelengths, spectrum_to_rgb};
 use std::rc::Rc;
-use std::{io::{self, Write}};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 
 // Image constants
 const ASPECT_RATIO: f32 = 3.0/2.0;
@@ -26,11 +70,42 @@ const IMAGE_WIDTH: i32 = 1200;
 const IMAGE_HEIGHT: i32 = (IMAGE_WIDTH as f32 / ASPECT_RATIO) as i32;
 const SAMPLES_PER_PIXEL: i32 = 70;
 const MAX_DEPTH: i32 = 50;
+const TILE_SIZE: usize = 32;
 
 fn main() 
 {
-    // World 
-    let world = random_scene();
+    let mut options = Options::from_args();
+
+    // Put regions rendered earlier together, instead of rendering
+    if let Some(paths) = &options.merge
+    {
+        let stdout = io::stdout();
+        if let Err(e) = merge(paths, &mut stdout.lock())
+        {
+            eprintln!("Could not merge regions: {}", e);
+        }
+        return;
+    }
+    if let Some(region) = options.region
+    {
+        options.region = region.clamped(IMAGE_WIDTH as usize, IMAGE_HEIGHT as usize);
+        if options.region.is_none()
+        {
+            eprintln!("The region is outside the image");
+            return;
+        }
+    }
+
+    // Render tiles handed out by a coordinator
+    if let Some(address) = &options.worker
+    {
+        if let Err(e) = worker::run(address)
+        {
+            eprintln!("Worker stopped: {}", e);
+        }
+        return;
+    }
+
     /*
     let mut world = HittableList::new();
     let material_ground = Rc::new(Lambertian::new(Color::new(0.8,0.8,0.0)));
@@ -47,6 +122,146 @@ fn main()
     world.add(Rc::new(Sphere::new(Point3::new(-1.0, 0.0, -1.0), -0.4, material_left)));
     world.add(Rc::new(Sphere::new(Point3::new(1.0, 0.0, -1.0), 0.5, material_right)));*/
 
+    // Show the image in a window as it renders, the image it got to is written once the window is closed
+    if options.preview
+    {
+        show_preview(&options);
+        return;
+    }
+
+    // Frames are rendered here, or handed out in tiles to workers
+    let render_frame: Box<dyn Fn(u32, f32) -> Frame + '_> = match &options.coordinator {
+        Some(address) => match Coordinator::start(address, &options) {
+            Ok(coordinator) => Box::new(move |number, time| coordinator.render(number, time)),
+            Err(e) => {
+                eprintln!("Could not listen on {}: {}", address, e);
+                return;
+            }
+        },
+        None => {
+            let scene = build_scene(&options);
+            let options = &options;
+            Box::new(move |number, time| render(camera_at(&scene, time).as_ref(), &scene, time, options, number))
+        }
+    };
+
+    // Render
+    match options.frames
+    {
+        Some(frames) => {
+            for frame in 0..frames
+            {
+                // The frames cover the animation from time 0 up to 1, so an animation that ends where it starts loops
+                let time = frame as f32 / frames as f32;
+                let path = format!("{}_{:04}.ppm", options.output, frame);
+                let file = match File::create(&path) {
+                    Ok(file) => file,
+                    Err(e) => {
+                        eprintln!("Could not create {}: {}", path, e);
+                        return;
+                    }
+                };
+
+                let mut handle = BufWriter::new(file);
+                let rendered = render_frame(frame, time);
+                match write_frame(&rendered, &options, &mut handle, Some(frame)).and_then(|_| handle.flush()) {
+                    Ok(()) => eprintln!("Wrote {}", path),
+                    Err(e) => eprintln!("Could not write {}: {}", path, e)
+                }
+            }
+        }
+        None => {
+            let stdout = io::stdout();
+            let mut handle = stdout.lock();
+            let rendered = render_frame(0, 0.0);
+            if let Err(e) = write_frame(&rendered, &options, &mut handle, None)
+            {
+                eprintln!("Could not write the image: {}", e);
+            }
+        }
+    }
+    eprintln!("\nDone.\n");
+}
+
+#[cfg(feature = "preview")]
+fn show_preview(options: &Options)
+{
+    let scene = build_scene(options);
+    if let Some(frame) = preview::window::run(&scene, camera_at(&scene, 0.0), options)
+    {
+        let stdout = io::stdout();
+        if let Err(e) = write_frame(&frame, options, &mut stdout.lock(), None)
+        {
+            eprintln!("Could not write the image: {}", e);
+        }
+    }
+}
+
+#[cfg(not(feature = "preview"))]
+fn show_preview(_options: &Options)
+{
+    eprintln!("The preview window is not in this build, build with --features preview to use it");
+}
+
+/**
+ * Builds the scene picked in the options, lit as asked for. The random numbers start from the seed,
+ * so every process builds the same scene, with its materials numbered the same way.
+ */
+fn build_scene(options: &Options) -> Scene
+{
+    seed_random(options.seed);
+
+    // World 
+    let scene = match options.scene.as_deref() {
+        Some("volumes") => volume_scene(options.scene_arg.clone()),
+        Some("lights") => light_scene(),
+        Some("principled") => principled_scene(),
+        Some("glass") => glass_scene(),
+        Some("coated") => coated_scene(),
+        Some("subsurface") => subsurface_scene(),
+        Some("projection") => projection_scene(options.scene_arg.as_deref()),
+        Some("bokeh") => bokeh_scene(options.scene_arg.clone()),
+        Some("tiltshift") => tilt_shift_scene(),
+        Some("animation") => animation_scene(),
+        Some("quadrics") => quadric_scene(),
+        Some("cornell") => cornell_scene(),
+        Some("csg") => csg_scene(),
+        _ => random_scene()
+    };
+
+    // Light the scene with an image from all around it or with daylight, in place of its background
+    match &options.environment {
+        Some(path) => match EnvironmentMap::load(path, options.env_rotation, options.env_intensity) {
+            Ok(environment) => scene.with_environment(Rc::new(environment)),
+            Err(error) => {
+                eprintln!("Could not load environment {}: {}", path, error);
+                scene
+            }
+        },
+        None if options.sky => {
+            let albedo = Color::new(options.ground_albedo, options.ground_albedo, options.ground_albedo);
+            let sky = Sky::new(options.sun_elevation, options.sun_azimuth, options.turbidity, albedo).with_sun_size(options.sun_size);
+            scene.with_environment(Rc::new(sky))
+        }
+        None => scene
+    }
+}
+
+/**
+ * Returns the camera to view the scene through at the given time: the scene's moving camera, its own
+ * camera, or else the view of the cover of the first book
+ */
+fn camera_at(scene: &Scene, time: f32) -> Rc<dyn Camera>
+{
+    if let Some(animation) = &scene.camera_animation
+    {
+        return Rc::new(animation.at(time));
+    }
+    if let Some(camera) = &scene.camera
+    {
+        return camera.clone();
+    }
+
     // Camera
     //let cam = Camera::default();
     let lookfrom = Point3::new(13.0,2.0,3.0);
@@ -54,46 +269,184 @@ fn main()
     let vup = Vec3::new(0.0,1.0,0.0);
     let dist_to_focus = 10.0;//(lookfrom-lookat).length();
     let aperture = 0.1;
-    let cam = Camera::new(lookfrom, lookat, vup, 20.0, ASPECT_RATIO, aperture, dist_to_focus);
+    Rc::new(Perspective::new(lookfrom, lookat, vup, 20.0, ASPECT_RATIO, aperture, dist_to_focus))
+}
 
-    // Render
-    let stdout = io::stdout();
-    let mut handle = stdout.lock();
+/**
+ * Splits the image into the tiles that are rendered one at a time. With a region only the region is split,
+ * along with the pixels around it that the filter spreads into it.
+ */
+fn tiles(options: &Options) -> Vec<Region>
+{
+    let (width, height) = (IMAGE_WIDTH as usize, IMAGE_HEIGHT as usize);
+    let area = match options.region {
+        Some(region) => region.grown(options.filter.margin(), width, height),
+        None => Region { x: 0, y: 0, width, height }
+    };
+    area.tiles(TILE_SIZE)
+}
 
-    let output = format!("P3\n{} {}\n255\n", IMAGE_WIDTH, IMAGE_HEIGHT);
-    handle.write_all(output.as_bytes());
+/**
+ * Seed of the random numbers for a tile of a frame, so the tile comes out the same wherever and in
+ * whatever order it is rendered
+ */
+fn tile_seed(seed: u64, frame: u32, tile: usize) -> u64
+{
+    mix_seed(mix_seed(seed, frame as u64), tile as u64)
+}
 
-    for j in (0..IMAGE_HEIGHT).rev()
+/**
+ * Renders the scene at the given time into a frame, one tile after another. Each tile is added to the frame
+ * the way a worker's tile would be, so the frame comes out the same as one put together from workers.
+ */
+fn render(cam: &dyn Camera, scene: &Scene, time: f32, options: &Options, number: u32) -> Frame
+{
+    let (width, height) = (IMAGE_WIDTH as usize, IMAGE_HEIGHT as usize);
+    let mut frame = Frame::new(width, height, options.filter);
+    let mut tile_frame = Frame::new(width, height, options.filter);
+    let mut preview = options.terminal_preview.map(TerminalPreview::new);
+    let tiles = tiles(options);
+    for (index, tile) in tiles.iter().enumerate()
     {
-        //eprintln!("{esc}c", esc = 27 as char);
-        //eprintln!("\rScanlines remaining: {} ", j);
-        for i in 0..IMAGE_WIDTH
+        seed_random(tile_seed(options.seed, number, index));
+        render_tile(cam, scene, time, options, &mut tile_frame, tile, SAMPLES_PER_PIXEL);
+        let area = tile.grown(options.filter.margin(), width, height);
+        frame.add(&area, &tile_frame.take(&area));
+
+        // Show the image so far each time a row of tiles is done
+        if let Some(preview) = &mut preview
         {
-            let mut pixel_color = Color::new(0.0,0.0,0.0);
-            for _ in 0..SAMPLES_PER_PIXEL
+            if tiles.get(index + 1).is_none_or(|next| next.y != tile.y)
             {
-                let u = ((i as f32) + random_number()) / (IMAGE_WIDTH-1) as f32;
-                let v = ((j as f32) + random_number()) / (IMAGE_HEIGHT-1) as f32;
-                
-                let ray = cam.get_ray(u, v);
-                pixel_color = pixel_color + ray_color(&ray, &world, MAX_DEPTH);
+                preview.show(&frame.color());
             }
-            write_color(&mut handle, &pixel_color, SAMPLES_PER_PIXEL);
         }
     }
-    eprintln!("\nDone.\n");
+    frame
+}
+
+/**
+ * Renders the given number of samples for each pixel of a tile into the frame
+ */
+fn render_tile(cam: &dyn Camera, scene: &Scene, time: f32, options: &Options, frame: &mut Frame, tile: &Region, samples: i32)
+{
+    for y in tile.y..tile.y + tile.height
+    {
+        let j = IMAGE_HEIGHT - 1 - y as i32;
+        for i in tile.x as i32..(tile.x + tile.width) as i32
+        {
+            for _ in 0..samples
+            {
+                let (du, dv) = (random_number(), random_number());
+                let u = ((i as f32) + du) / (IMAGE_WIDTH-1) as f32;
+                let v = ((j as f32) + dv) / (IMAGE_HEIGHT-1) as f32;
+                // Position of the sample on the film, in pixels from the top left corner
+                let (x, y) = (i as f32 + du, (IMAGE_HEIGHT - j) as f32 - dv);
+
+                let mut features = Features::default();
+                let ray = match cam.get_ray(u, v) {
+                    Some(ray) => ray.with_time(time),
+                    None => {
+                        frame.add_sample(x, y, Color::new(0.0,0.0,0.0), &features);
+                        continue;
+                    }
+                };
+                let color = if let Some(mode) = options.debug {
+                    debug_color(&ray, scene, mode, MAX_DEPTH)
+                } else if options.spectral {
+                    let wavelengths = sample_wavelengths();
+                    let radiance = ray_color(&ray.with_spectrum(Some(wavelengths)), scene, MAX_DEPTH, &mut features);
+                    features.albedo = spectrum_to_rgb(&features.albedo, &wavelengths);
+                    spectrum_to_rgb(&radiance, &wavelengths)
+                } else {
+                    ray_color(&ray, scene, MAX_DEPTH, &mut features)
+                };
+                frame.add_sample(x, y, color, &features);
+            }
+        }
+    }
+}
+
+/**
+ * Writes the rendered image, denoised if asked for. The noisy image and the output variables go to files
+ * next to it, named after the output prefix and the number of the frame in an animation. Errors writing
+ * the image itself are returned, the other files report their own.
+ */
+fn write_frame(frame: &Frame, options: &Options, handle: &mut dyn Write, number: Option<u32>) -> io::Result<()>
+{
+    let path = |name: &str, extension: &str| match number {
+        Some(number) => format!("{}_{}_{:04}.{}", options.output, name, number, extension),
+        None => format!("{}_{}.{}", options.output, name, extension)
+    };
+
+    // A region is cut out before denoising, so the black around it does not bleed in
+    let (width, height) = (IMAGE_WIDTH as usize, IMAGE_HEIGHT as usize);
+    let crop = |image: Image| match options.region {
+        Some(region) => region.crop(&image),
+        None => image
+    };
+    let finish = |image: Image| match options.region {
+        Some(region) if options.full_frame => region.uncrop(&image, width, height),
+        _ => image
+    };
+    let comment = options.region.map(|region| region.comment(width, height));
+
+    let color = crop(frame.color());
+    if options.denoise
+    {
+        let denoised = denoise(&color, &crop(frame.albedo()), &crop(frame.normal()));
+        finish(denoised).write_ppm(handle, comment.as_deref())?;
+        if options.keep_noisy
+        {
+            save(&path("noisy", "ppm"), |handle| finish(color).write_ppm(handle, comment.as_deref()));
+        }
+    } else {
+        finish(color).write_ppm(handle, comment.as_deref())?;
+    }
+
+    if options.aovs
+    {
+        let aovs = [
+            ("depth", frame.depth()),
+            ("normal", frame.normal()),
+            ("albedo", frame.albedo()),
+            ("material_id", frame.material_id()),
+            ("object_id", frame.object_id())
+        ];
+        for (name, image) in aovs
+        {
+            save(&path(name, "pfm"), |handle| finish(crop(image)).write_pfm(handle));
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Creates the file and writes to it, reporting how that went
+ */
+fn save(path: &str, write: impl FnOnce(&mut dyn Write) -> io::Result<()>)
+{
+    let result = File::create(path).and_then(|file| {
+        let mut handle = BufWriter::new(file);
+        write(&mut handle)?;
+        handle.flush()
+    });
+    match result {
+        Ok(()) => eprintln!("Wrote {}", path),
+        Err(e) => eprintln!("Could not write {}: {}", path, e)
+    }
 }
 
 
 /**
  * Generates image on the cover of the first book
  */
-fn random_scene() -> HittableList
+fn random_scene() -> Scene
 {
     let mut world: HittableList = HittableList::new();
 
     let ground_material = Rc::new(Lambertian::new(Color::new(0.5,0.5,0.5)));
-    world.add(Rc::new(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, ground_material)));
+    world.add(Rc::new(Plane::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), ground_material)));
 
     for a in -11..11
     {
@@ -138,5 +491,440 @@ fn random_scene() -> HittableList
     let material3 = Rc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0));
     world.add(Rc::new(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.0, material3)));
 
-    return world;
+    return Scene::new(world, HittableList::new(), None);
+}
+
+
+/**
+ * Generates a scene with participating media: a noisy cloud, coloured smoke and a glass ball in fog.
+ * If a voxel grid file is given it is placed in front of the cloud.
+ */
+fn volume_scene(grid_path: Option<String>) -> Scene
+{
+    let mut world: HittableList = HittableList::new();
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5,0.5,0.5)));
+    world.add(Rc::new(Plane::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), ground_material)));
+
+    // Forward scattering cloud with density from noise
+    let cloud_boundary = Rc::new(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, Rc::new(Lambertian::default())));
+    world.add(Rc::new(Volume::new(cloud_boundary, Rc::new(NoiseDensity::new(3.0, 4.0)),
+        Color::new(0.05, 0.05, 0.05), Color::new(1.0, 1.0, 1.0), 0.6)));
+
+    // Smoke that absorbs red and scatters blue
+    let smoke_boundary = Rc::new(Sphere::new(Point3::new(-4.0, 1.0, 0.0), 1.0, Rc::new(Lambertian::default())));
+    world.add(Rc::new(Volume::new(smoke_boundary, Rc::new(ConstantDensity::new(1.0)),
+        Color::new(1.5, 0.4, 0.1), Color::new(0.2, 0.5, 1.5), 0.0)));
+
+    // Glass ball inside a thin backward scattering fog
+    world.add(Rc::new(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.0, Rc::new(Dielectric::new(1.5)))));
+    let fog_boundary = Rc::new(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.6, Rc::new(Lambertian::default())));
+    world.add(Rc::new(Volume::new(fog_boundary, Rc::new(ConstantDensity::new(0.3)),
+        Color::new(0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0), -0.3)));
+
+    if let Some(path) = grid_path
+    {
+        let min = Point3::new(1.0, 0.0, 1.5);
+        let max = Point3::new(3.0, 2.0, 3.5);
+        match GridDensity::load(&path, min, max)
+        {
+            Ok(grid) => {
+                let grid_boundary = Rc::new(Sphere::new(Point3::new(2.0, 1.0, 2.5), 1.75, Rc::new(Lambertian::default())));
+                world.add(Rc::new(Volume::new(grid_boundary, Rc::new(grid),
+                    Color::new(0.1, 0.1, 0.1), Color::new(0.9, 0.9, 0.9), 0.3)));
+            }
+            Err(e) => eprintln!("Could not load voxel grid {}: {}", path, e)
+        }
+    }
+
+    Scene::new(world, HittableList::new(), None)
+}
+
+/**
+ * Generates a dark scene lit only by small coloured lights, which need light sampling to converge
+ */
+fn light_scene() -> Scene
+{
+    let mut world: HittableList = HittableList::new();
+    let mut lights: HittableList = HittableList::new();
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5,0.5,0.5)));
+    world.add(Rc::new(Plane::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), ground_material)));
+
+    world.add(Rc::new(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, Rc::new(Dielectric::new(1.5)))));
+    world.add(Rc::new(Sphere::new(Point3::new(-4.0, 1.0, 0.0), 1.0, Rc::new(Lambertian::new(Color::new(0.4, 0.2, 0.1))))));
+    world.add(Rc::new(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.0, Rc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.2)))));
+
+    // Thin smoke around the diffuse ball, lit through its transmittance
+    let smoke_boundary = Rc::new(Sphere::new(Point3::new(-4.0, 1.0, 0.0), 1.8, Rc::new(Lambertian::default())));
+    world.add(Rc::new(Volume::new(smoke_boundary, Rc::new(ConstantDensity::new(0.2)),
+        Color::new(0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0), 0.3)));
+
+    let light_spheres = [
+        (Point3::new(-2.0, 3.0, 2.0), 0.15, Color::new(40.0, 30.0, 20.0)),
+        (Point3::new(2.0, 2.5, -2.0), 0.1, Color::new(10.0, 20.0, 60.0)),
+        (Point3::new(0.0, 0.3, 2.5), 0.05, Color::new(80.0, 10.0, 10.0))
+    ];
+    for (center, radius, emit) in light_spheres
+    {
+        let light = Rc::new(Sphere::new(center, radius, Rc::new(DiffuseLight::new(emit))));
+        world.add(light.clone());
+        lights.add(light);
+    }
+
+    Scene::new(world, lights, Some(Color::new(0.0, 0.0, 0.0)))
+}
+
+/**
+ * Generates rows of principled spheres, going from smooth to rough. The front row is dielectric,
+ * the middle row metallic and the back row measured gold, copper and silver.
+ */
+fn principled_scene() -> Scene
+{
+    let mut world: HittableList = HittableList::new();
+    let mut lights: HittableList = HittableList::new();
+
+    let ground_material = Rc::new(Principled::new(Color::new(0.5,0.5,0.5), 0.6, 0.0, 0.5));
+    world.add(Rc::new(Plane::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), ground_material)));
+
+    for i in 0..5
+    {
+        let roughness = i as f32 / 4.0;
+        let z = -2.4 + 1.2 * i as f32;
+
+        let plastic = Rc::new(Principled::new(Color::new(0.8, 0.1, 0.1), roughness, 0.0, 0.5));
+        world.add(Rc::new(Sphere::new(Point3::new(2.0, 0.5, z), 0.5, plastic)));
+
+        let metal = Rc::new(Principled::new(Color::new(0.9, 0.9, 0.9), roughness, 1.0, 0.5));
+        world.add(Rc::new(Sphere::new(Point3::new(0.0, 0.5, z), 0.5, metal)));
+    }
+
+    let gold = Rc::new(Principled::conductor(Color::new(0.143, 0.374, 1.442), Color::new(3.983, 2.385, 1.603), 0.2));
+    let copper = Rc::new(Principled::conductor(Color::new(0.200, 0.924, 1.102), Color::new(3.912, 2.452, 2.142), 0.35));
+    let silver = Rc::new(Principled::conductor(Color::new(0.155, 0.117, 0.138), Color::new(4.828, 3.122, 2.147), 0.05));
+    world.add(Rc::new(Sphere::new(Point3::new(-2.0, 0.5, -1.2), 0.5, gold)));
+    world.add(Rc::new(Sphere::new(Point3::new(-2.0, 0.5, 0.0), 0.5, copper)));
+    world.add(Rc::new(Sphere::new(Point3::new(-2.0, 0.5, 1.2), 0.5, silver)));
+
+    let light = Rc::new(Sphere::new(Point3::new(6.0, 6.0, 4.0), 1.0, Rc::new(DiffuseLight::new(Color::new(15.0, 15.0, 15.0)))));
+    world.add(light.clone());
+    lights.add(light);
+
+    Scene::new(world, lights, Some(Color::new(0.1, 0.1, 0.15)))
+}
+
+/**
+ * Generates coloured and dispersive glass, lit by a small bright light to show the caustics
+ */
+fn glass_scene() -> Scene
+{
+    let mut world: HittableList = HittableList::new();
+    let mut lights: HittableList = HittableList::new();
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.7,0.7,0.7)));
+    world.add(Rc::new(Plane::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), ground_material)));
+
+    // Dense flint glass, which spreads the light into a rainbow
+    let flint = Dispersion::Sellmeier {
+        b: [1.737_597, 0.313_747_35, 1.898_781],
+        c: [0.013_188_707, 0.062_306_814, 155.236_3]
+    };
+    world.add(Rc::new(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, Rc::new(Dielectric::new(1.78).with_dispersion(flint)))));
+
+    // Crown glass with a weak Cauchy dispersion and a green tint
+    let crown = Dielectric::new(1.52)
+        .with_dispersion(Dispersion::Cauchy { a: 1.5046, b: 0.00420 })
+        .with_tint(Color::new(0.4, 0.85, 0.5), 1.0);
+    world.add(Rc::new(Sphere::new(Point3::new(-4.0, 1.0, 0.0), 1.0, Rc::new(crown))));
+
+    // Deep red glass without dispersion
+    let ruby = Dielectric::new(1.76).with_absorption(Color::new(0.2, 2.5, 2.0));
+    world.add(Rc::new(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.0, Rc::new(ruby))));
+
+    let light = Rc::new(Sphere::new(Point3::new(0.0, 8.0, -6.0), 0.5, Rc::new(DiffuseLight::new(Color::new(150.0, 150.0, 150.0)))));
+    world.add(light.clone());
+    lights.add(light);
+
+    Scene::new(world, lights, Some(Color::new(0.02, 0.02, 0.03)))
+}
+
+/**
+ * Generates coated materials: clear coated car paint, a soap bubble, an oxidised metal and an anti-reflective
+ * coated lens next to an uncoated one
+ */
+fn coated_scene() -> Scene
+{
+    let mut world: HittableList = HittableList::new();
+    let mut lights: HittableList = HittableList::new();
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5,0.5,0.5)));
+    world.add(Rc::new(Plane::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), ground_material)));
+
+    // Car paint, a clear coat over a diffuse and over a metallic base
+    let paint = Rc::new(Coated::clear_coat(Rc::new(Lambertian::new(Color::new(0.6, 0.05, 0.05))), 1.5));
+    world.add(Rc::new(Sphere::new(Point3::new(0.0, 1.0, -3.0), 1.0, paint)));
+    let metallic_paint = Rc::new(Coated::clear_coat(Rc::new(Principled::new(Color::new(0.1, 0.3, 0.7), 0.4, 1.0, 0.5)), 1.5));
+    world.add(Rc::new(Sphere::new(Point3::new(0.0, 1.0, 3.0), 1.0, metallic_paint)));
+
+    // Soap bubble, a film of water around air
+    let bubble = Rc::new(Coated::thin_film(Rc::new(Dielectric::new(1.0)), 380.0, 1.33, 1.0));
+    world.add(Rc::new(Sphere::new(Point3::new(2.0, 1.2, 0.0), 1.2, bubble)));
+
+    // Oxide layer on steel, like a heat tinted metal
+    let tinted = Rc::new(Coated::thin_film(Rc::new(Metal::new(Color::new(0.6, 0.6, 0.6), 0.0)), 250.0, 2.2, 2.5));
+    world.add(Rc::new(Sphere::new(Point3::new(-2.0, 1.0, 0.0), 1.0, tinted)));
+
+    // Lenses with and without a quarter wave magnesium fluoride coating
+    let lens = Rc::new(Coated::thin_film(Rc::new(Dielectric::new(1.5)), 100.0, 1.38, 1.5));
+    world.add(Rc::new(Sphere::new(Point3::new(4.0, 0.5, -1.0), 0.5, lens)));
+    world.add(Rc::new(Sphere::new(Point3::new(4.0, 0.5, 1.0), 0.5, Rc::new(Dielectric::new(1.5)))));
+
+    let light = Rc::new(Sphere::new(Point3::new(6.0, 8.0, 2.0), 1.0, Rc::new(DiffuseLight::new(Color::new(10.0, 10.0, 10.0)))));
+    world.add(light.clone());
+    lights.add(light);
+
+    Scene::new(world, lights, None)
+}
+
+/**
+ * Generates subsurface scattering spheres of marble, wax and skin, next to a Lambertian sphere of the same color
+ */
+fn subsurface_scene() -> Scene
+{
+    let mut world: HittableList = HittableList::new();
+    let mut lights: HittableList = HittableList::new();
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5,0.5,0.5)));
+    world.add(Rc::new(Plane::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), ground_material)));
+
+    let boundary = |center: Point3| -> Rc<Sphere> {
+        Rc::new(Sphere::new(center, 1.0, Rc::new(Lambertian::default())))
+    };
+
+    let marble = Subsurface::new(boundary(Point3::new(0.0, 1.0, -2.5)), Color::new(0.12, 0.13, 0.15), Color::new(0.999, 0.998, 0.996), 0.0, 1.5);
+    world.add(Rc::new(marble));
+
+    let wax = Subsurface::new(boundary(Point3::new(0.0, 1.0, 0.0)), Color::new(0.3, 0.2, 0.1), Color::new(0.99, 0.95, 0.8), 0.0, 1.45);
+    world.add(Rc::new(wax));
+
+    let skin = Subsurface::new(boundary(Point3::new(0.0, 1.0, 2.5)), Color::new(0.37, 0.14, 0.08), Color::new(0.95, 0.8, 0.7), 0.0, 1.4);
+    world.add(Rc::new(skin));
+
+    world.add(Rc::new(Sphere::new(Point3::new(-3.0, 1.0, 0.0), 1.0, Rc::new(Lambertian::new(Color::new(0.9, 0.75, 0.55))))));
+
+    let light = Rc::new(Sphere::new(Point3::new(-4.0, 6.0, 6.0), 1.0, Rc::new(DiffuseLight::new(Color::new(20.0, 20.0, 20.0)))));
+    world.add(light.clone());
+    lights.add(light);
+
+    Scene::new(world, lights, Some(Color::new(0.05, 0.05, 0.07)))
+}
+
+/**
+ * Generates the cover scene, seen through the named projection: an orthographic elevation, a fisheye
+ * from just above the ground or a panorama from between the spheres
+ */
+fn projection_scene(projection: Option<&str>) -> Scene
+{
+    let scene = random_scene();
+    let vup = Vec3::new(0.0,1.0,0.0);
+
+    let camera: Rc<dyn Camera> = match projection {
+        Some("orthographic") => Rc::new(Orthographic::new(Point3::new(0.0, 2.0, 20.0), Point3::new(0.0, 2.0, 0.0), vup, 4.0, ASPECT_RATIO)),
+        Some("fisheye") => Rc::new(Fisheye::new(Point3::new(6.0, 0.5, 1.5), Point3::new(0.0, 1.0, 0.0), vup, 180.0, ASPECT_RATIO)),
+        Some("equirectangular") => Rc::new(Equirectangular::new(Point3::new(2.0, 1.0, 0.5), Point3::new(0.0, 1.0, 0.0), vup)),
+        _ => Rc::new(Perspective::new(Point3::new(13.0,2.0,3.0), Point3::new(0.0,0.0,0.0), vup, 20.0, ASPECT_RATIO, 0.1, 10.0))
+    };
+
+    scene.with_camera(camera)
+}
+
+/**
+ * Generates small bright lights far behind the point in focus, to show the shape of the out of focus highlights.
+ * The aperture is a hexagon, or the image mask at the given path, with cat-eye vignetting.
+ */
+fn bokeh_scene(mask_path: Option<String>) -> Scene
+{
+    let mut world: HittableList = HittableList::new();
+    let mut lights: HittableList = HittableList::new();
+
+    world.add(Rc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 0.5, Rc::new(Lambertian::new(Color::new(0.7, 0.3, 0.1))))));
+    for a in -4..=4
+    {
+        for b in -3..=3
+        {
+            let color = Color::new(0.5, 0.5, 0.5) + random_vec().const_mul(0.5);
+            let center = Point3::new(a as f32 * 5.0, b as f32 * 5.0, -40.0);
+            let light = Rc::new(Sphere::new(center, 0.4, Rc::new(DiffuseLight::new(color.const_mul(4.0)))));
+            world.add(light.clone());
+            lights.add(light);
+        }
+    }
+    let fill = Rc::new(Sphere::new(Point3::new(4.0, 4.0, 4.0), 1.0, Rc::new(DiffuseLight::new(Color::new(4.0, 4.0, 4.0)))));
+    world.add(fill.clone());
+    lights.add(fill);
+
+    let aperture = match mask_path {
+        Some(path) => match Image::load(&path) {
+            Ok(mask) => Aperture::Mask(Rc::new(Mask::new(&mask))),
+            Err(e) => {
+                eprintln!("Could not load aperture mask: {}", e);
+                Aperture::Polygon { blades: 6, rotation: 0.0 }
+            }
+        },
+        None => Aperture::Polygon { blades: 6, rotation: 0.0 }
+    };
+
+    let camera = Perspective::new(Point3::new(0.0, 0.0, 5.0), Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0,1.0,0.0), 40.0, ASPECT_RATIO, 0.5, 5.0)
+        .with_aperture(aperture)
+        .with_cat_eye(0.6);
+
+    Scene::new(world, lights, Some(Color::new(0.0, 0.0, 0.0))).with_camera(Rc::new(camera))
+}
+
+/**
+ * Generates the cover scene seen from above with a tilted plane of focus, which makes it look like a miniature
+ */
+fn tilt_shift_scene() -> Scene
+{
+    let camera = Perspective::new(Point3::new(13.0, 6.0, 3.0), Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0,1.0,0.0), 30.0, ASPECT_RATIO, 0.6, 14.0)
+        .with_tilt(-12.0, 0.0)
+        .with_shift(0.0, -0.1);
+
+    random_scene().with_camera(Rc::new(camera))
+}
+
+/**
+ * Generates a turntable: the camera circles the three large spheres of the cover while a small
+ * metal sphere orbits them and a glass sphere bounces
+ */
+fn animation_scene() -> Scene
+{
+    let mut world: HittableList = HittableList::new();
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5,0.5,0.5)));
+    world.add(Rc::new(Plane::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), ground_material)));
+
+    world.add(Rc::new(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, Rc::new(Dielectric::new(1.5)))));
+    world.add(Rc::new(Sphere::new(Point3::new(-4.0, 1.0, 0.0), 1.0, Rc::new(Lambertian::new(Color::new(0.4, 0.2, 0.1))))));
+    world.add(Rc::new(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.0, Rc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0)))));
+
+    // Rotating around the y axis carries the sphere around the center
+    let orbiter = Rc::new(Sphere::new(Point3::new(6.0, 0.4, 0.0), 0.4, Rc::new(Metal::new(Color::new(0.8, 0.3, 0.3), 0.1))));
+    let orbit = Track::new(vec![(0.0, 0.0), (1.0, 360.0)]);
+    world.add(Rc::new(Animated::new(orbiter, Track::constant(Vec3::new(0.0, 0.0, 0.0)), orbit)));
+
+    let bouncer = Rc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 0.5, Rc::new(Dielectric::new(1.5))));
+    let bounce = Track::new(vec![
+        (0.0, Vec3::new(0.0, 0.5, 3.0)),
+        (0.25, Vec3::new(0.0, 2.5, 3.0)),
+        (0.5, Vec3::new(0.0, 0.5, 3.0)),
+        (0.75, Vec3::new(0.0, 2.5, 3.0)),
+        (1.0, Vec3::new(0.0, 0.5, 3.0))]);
+    world.add(Rc::new(Animated::new(bouncer, bounce, Track::constant(0.0))));
+
+    // A full circle around the scene, zooming in and pulling focus to the front halfway
+    let circle = (0..=8).map(|i| {
+        let angle = i as f32 * PI as f32 / 4.0;
+        (i as f32 / 8.0, Point3::new(13.0 * angle.cos(), 2.5, 13.0 * angle.sin()))
+    }).collect();
+    let camera = CameraAnimation::new(Track::new(circle), Track::constant(Point3::new(0.0, 0.5, 0.0)), Vec3::new(0.0,1.0,0.0), ASPECT_RATIO)
+        .with_vfov(Track::new(vec![(0.0, 30.0), (0.5, 20.0), (1.0, 30.0)]))
+        .with_aperture(Track::constant(0.1))
+        .with_focus_dist(Track::new(vec![(0.0, 13.0), (0.5, 10.0), (1.0, 13.0)]));
+
+    Scene::new(world, HittableList::new(), None).with_camera_animation(camera)
+}
+
+/**
+ * Generates a row of the other analytic shapes: a cylinder, a cone, a disk, an annulus and a tilted torus
+ */
+fn quadric_scene() -> Scene
+{
+    let mut world: HittableList = HittableList::new();
+    let mut lights: HittableList = HittableList::new();
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5,0.5,0.5)));
+    world.add(Rc::new(Plane::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), ground_material)));
+
+    let up = Vec3::new(0.0,1.0,0.0);
+    world.add(Rc::new(Cylinder::new(Point3::new(-5.0, 0.0, 0.0), up.const_mul(2.0), 0.8, Rc::new(Lambertian::new(Color::new(0.7, 0.2, 0.2))))));
+    world.add(Rc::new(Cone::new(Point3::new(-2.5, 0.0, 0.0), up.const_mul(2.0), 0.9, Rc::new(Metal::new(Color::new(0.8, 0.7, 0.4), 0.2)))));
+    world.add(Rc::new(Disk::new(Point3::new(-0.2, 1.0, 0.0), Vec3::new(0.3, 0.2, 1.0), 0.9, Rc::new(Lambertian::new(Color::new(0.2, 0.5, 0.7))))));
+    let annulus = Disk::new(Point3::new(2.2, 1.1, 0.0), Vec3::new(-0.3, 0.0, 1.0), 1.0, Rc::new(Lambertian::new(Color::new(0.2, 0.7, 0.3)))).with_hole(0.5);
+    world.add(Rc::new(annulus));
+    world.add(Rc::new(Torus::new(Point3::new(4.9, 0.9, 0.0), Vec3::new(0.0, 1.0, 1.0), 0.8, 0.3, Rc::new(Dielectric::new(1.5)))));
+
+    let light = Rc::new(Sphere::new(Point3::new(4.0, 8.0, 8.0), 1.0, Rc::new(DiffuseLight::new(Color::new(12.0, 12.0, 12.0)))));
+    world.add(light.clone());
+    lights.add(light);
+
+    let camera = Perspective::new(Point3::new(0.0, 3.0, 14.0), Point3::new(0.0, 1.0, 0.0), up, 35.0, ASPECT_RATIO, 0.0, 14.0);
+    Scene::new(world, lights, None).with_camera(Rc::new(camera))
+}
+
+/**
+ * Cornell box, with walls and a ceiling light made of quads
+ */
+fn cornell_scene() -> Scene
+{
+    let mut world: HittableList = HittableList::new();
+    let mut lights: HittableList = HittableList::new();
+
+    let red = Rc::new(Lambertian::new(Color::new(0.65, 0.05, 0.05)));
+    let white = Rc::new(Lambertian::new(Color::new(0.73, 0.73, 0.73)));
+    let green = Rc::new(Lambertian::new(Color::new(0.12, 0.45, 0.15)));
+
+    world.add(Rc::new(Quad::new(Point3::new(555.0, 0.0, 0.0), Vec3::new(0.0, 555.0, 0.0), Vec3::new(0.0, 0.0, 555.0), green)));
+    world.add(Rc::new(Quad::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 555.0, 0.0), Vec3::new(0.0, 0.0, 555.0), red)));
+    world.add(Rc::new(Quad::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(555.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 555.0), white.clone())));
+    world.add(Rc::new(Quad::new(Point3::new(555.0, 555.0, 555.0), Vec3::new(-555.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -555.0), white.clone())));
+    world.add(Rc::new(Quad::new(Point3::new(0.0, 0.0, 555.0), Vec3::new(555.0, 0.0, 0.0), Vec3::new(0.0, 555.0, 0.0), white.clone())));
+
+    world.add(Rc::new(Cylinder::new(Point3::new(370.0, 0.0, 350.0), Vec3::new(0.0, 330.0, 0.0), 80.0, white)));
+    world.add(Rc::new(Sphere::new(Point3::new(190.0, 90.0, 190.0), 90.0, Rc::new(Dielectric::new(1.5)))));
+
+    // Facing down into the box
+    let light = Rc::new(Quad::new(Point3::new(343.0, 554.0, 332.0), Vec3::new(-130.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -105.0), Rc::new(DiffuseLight::new(Color::new(15.0, 15.0, 15.0)))));
+    world.add(light.clone());
+    lights.add(light);
+
+    let camera = Perspective::new(Point3::new(278.0, 278.0, -800.0), Point3::new(278.0, 278.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 40.0, ASPECT_RATIO, 0.0, 10.0);
+    Scene::new(world, lights, Some(Color::new(0.0, 0.0, 0.0))).with_camera(Rc::new(camera))
+}
+
+/**
+ * Generates solids made of other objects: a hollow glass ball, a lens and a ball with holes bored through it
+ */
+fn csg_scene() -> Scene
+{
+    let mut world: HittableList = HittableList::new();
+    let mut lights: HittableList = HittableList::new();
+
+    let up = Vec3::new(0.0,1.0,0.0);
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5,0.5,0.5)));
+    world.add(Rc::new(Plane::new(Point3::new(0.0, 0.0, 0.0), up, ground_material)));
+
+    let glass = Rc::new(Dielectric::new(1.5));
+    let outer = Rc::new(Sphere::new(Point3::new(-3.0, 1.0, 0.0), 1.0, glass.clone()));
+    let inner = Rc::new(Sphere::new(Point3::new(-3.0, 1.0, 0.0), 0.85, glass.clone()));
+    world.add(Rc::new(Csg::new(Operation::Difference, outer, inner)));
+
+    // Where two balls overlap, curved on both sides
+    let front = Rc::new(Sphere::new(Point3::new(0.0, 1.2, -1.6), 2.0, glass.clone()));
+    let back = Rc::new(Sphere::new(Point3::new(0.0, 1.2, 1.6), 2.0, glass));
+    world.add(Rc::new(Csg::new(Operation::Intersection, front, back)));
+
+    let red = Rc::new(Lambertian::new(Color::new(0.7, 0.15, 0.1)));
+    let ball = Rc::new(Sphere::new(Point3::new(3.0, 1.0, 0.0), 1.0, red.clone()));
+    let across = Rc::new(Cylinder::new(Point3::new(1.8, 1.0, 0.0), Vec3::new(2.4, 0.0, 0.0), 0.45, red.clone()));
+    let through = Rc::new(Cylinder::new(Point3::new(3.0, 1.0, -1.2), Vec3::new(0.0, 0.0, 2.4), 0.45, red));
+    let holes = Rc::new(Csg::new(Operation::Union, across, through));
+    world.add(Rc::new(Csg::new(Operation::Difference, ball, holes)));
+
+    let light = Rc::new(Sphere::new(Point3::new(2.0, 8.0, 6.0), 1.0, Rc::new(DiffuseLight::new(Color::new(12.0, 12.0, 12.0)))));
+    world.add(light.clone());
+    lights.add(light);
+
+    let camera = Perspective::new(Point3::new(1.0, 3.5, 12.0), Point3::new(0.0, 1.0, 0.0), up, 30.0, ASPECT_RATIO, 0.0, 12.0);
+    Scene::new(world, lights, None).with_camera(Rc::new(camera))
 }
\ No newline at end of file
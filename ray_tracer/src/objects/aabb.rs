@@ -0,0 +1,88 @@
+use crate::vectors::{ray::Ray, vec3::{Point3, Vec3}};
+
+// Thickness given to the boxes of flat objects
+const BOX_PADDING: f32 = 1e-4;
+
+/**
+ * An axis aligned bounding box, the smallest box along the axes that holds an object
+ */
+#[derive(Copy, Clone)]
+pub struct Aabb
+{
+    pub min: Point3,
+    pub max: Point3
+}
+
+impl Aabb
+{
+    /**
+     * Returns the box between two corners, given in any order
+     */
+    pub fn new(a: Point3, b: Point3) -> Aabb
+    {
+        Aabb
+        {
+            min: Point3::new(a.x().min(b.x()), a.y().min(b.y()), a.z().min(b.z())),
+            max: Point3::new(a.x().max(b.x()), a.y().max(b.y()), a.z().max(b.z()))
+        }
+    }
+
+    /**
+     * Returns the box around all the points, which must not be empty
+     */
+    pub fn from_points(points: &[Point3]) -> Aabb
+    {
+        points.iter().fold(Aabb::new(points[0], points[0]), |bounds, p| bounds.surrounding(&Aabb::new(*p, *p)))
+    }
+
+    /**
+     * Returns the box around both boxes
+     */
+    pub fn surrounding(&self, other: &Aabb) -> Aabb
+    {
+        Aabb
+        {
+            min: Point3::new(self.min.x().min(other.min.x()), self.min.y().min(other.min.y()), self.min.z().min(other.min.z())),
+            max: Point3::new(self.max.x().max(other.max.x()), self.max.y().max(other.max.y()), self.max.z().max(other.max.z()))
+        }
+    }
+
+    /**
+     * Returns the box grown a little on all sides, so the box of a flat object along the axes still has volume
+     */
+    pub fn padded(&self) -> Aabb
+    {
+        let padding = Vec3::new(BOX_PADDING, BOX_PADDING, BOX_PADDING);
+        Aabb::new(self.min - padding, self.max + padding)
+    }
+
+    /**
+     * Returns the part of the ray between t_min and t_max that is inside the box, None if it misses the box
+     */
+    pub fn hit(&self, r: &Ray, t_min: f32, t_max: f32) -> Option<(f32, f32)>
+    {
+        let (origin, direction) = (r.origin(), r.direction());
+        let mut t0 = t_min;
+        let mut t1 = t_max;
+        for (o, d, min, max) in [
+            (origin.x(), direction.x(), self.min.x(), self.max.x()),
+            (origin.y(), direction.y(), self.min.y(), self.max.y()),
+            (origin.z(), direction.z(), self.min.z(), self.max.z())]
+        {
+            // Slabs between the planes on either side, a ray parallel to them gets infinite distances
+            let inv_d = 1.0 / d;
+            let (mut near, mut far) = ((min - o) * inv_d, (max - o) * inv_d);
+            if inv_d < 0.0
+            {
+                std::mem::swap(&mut near, &mut far);
+            }
+            t0 = if near > t0 { near } else { t0 };
+            t1 = if far < t1 { far } else { t1 };
+            if t1 < t0
+            {
+                return None;
+            }
+        }
+        Some((t0, t1))
+    }
+}
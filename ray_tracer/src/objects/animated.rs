@@ -0,0 +1,86 @@
+use std::rc::Rc;
+
+use crate::{vectors::{ray::Ray, vec3::{Color, Vec3, rotate_y}}, utility::animation::Track};
+
+use super::{hittable::Hittable, hit_record::HitRecord, material::material::MaterialIds, medium::volume::Traversal};
+
+/**
+ * An object moved by keyframed transforms: a rotation around the y axis through its own origin, followed
+ * by a translation. The transform is taken at the time of each ray. Animated objects are not sampled as lights.
+ */
+pub struct Animated
+{
+    object: Rc<dyn Hittable>,
+    translation: Track<Vec3>,
+    rotation: Track<f32> // Degrees around the y axis
+}
+
+impl Animated
+{
+    pub fn new(object: Rc<dyn Hittable>, translation: Track<Vec3>, rotation: Track<f32>) -> Animated
+    {
+        Animated
+        {
+            object,
+            translation,
+            rotation
+        }
+    }
+
+    /**
+     * Returns the ray in the object's own space, with the rotation and translation at the ray's time
+     */
+    fn to_object(&self, r: &Ray) -> (Ray, f32, Vec3)
+    {
+        let angle = self.rotation.at(r.time()).to_radians();
+        let offset = self.translation.at(r.time());
+        let origin = rotate_y(&(r.origin() - offset), -angle);
+        let direction = rotate_y(&r.direction(), -angle);
+        (r.continued(origin, direction), angle, offset)
+    }
+}
+
+impl Hittable for Animated
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        let (local, angle, offset) = self.to_object(r);
+        if !self.object.hit(&local, t_min, t_max, hit_rec)
+        {
+            return false;
+        }
+
+        // Rotations keep the ray parameter and which side the normal is on
+        hit_rec.p = rotate_y(&hit_rec.p, angle) + offset;
+        hit_rec.normal = rotate_y(&hit_rec.normal, angle);
+        true
+    }
+
+    fn transmittance(&self, r: &Ray, t_min: f32, t_max: f32) -> Color
+    {
+        let (local, _, _) = self.to_object(r);
+        self.object.transmittance(&local, t_min, t_max)
+    }
+
+    fn is_medium(&self) -> bool
+    {
+        self.object.is_medium()
+    }
+
+    fn traverse(&self, r: &Ray, t_min: f32, t_max: f32) -> Option<Traversal>
+    {
+        let (local, angle, offset) = self.to_object(r);
+        let mut traversal = self.object.traverse(&local, t_min, t_max)?;
+        for rec in traversal.scatter.iter_mut().chain(traversal.exit.iter_mut())
+        {
+            rec.p = rotate_y(&rec.p, angle) + offset;
+            rec.normal = rotate_y(&rec.normal, angle);
+        }
+        Some(traversal)
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        self.object.number_materials(ids);
+    }
+}
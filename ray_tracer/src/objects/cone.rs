@@ -0,0 +1,119 @@
+use std::{cell::Cell, rc::Rc};
+
+use crate::{vectors::{onb::Onb, ray::Ray, vec3::{Point3, Vec3}}, utility::rtweekend::{INFINITY, PI}};
+
+use super::{aabb::Aabb, disk::Disk, hittable::Hittable, hit_record::HitRecord, material::material::{Material, MaterialIds}};
+
+// Rays closer to parallel with the side than this are taken as parallel
+const PARALLEL_EPSILON: f32 = 1e-8;
+
+/**
+ * A round cone closed at its base, standing on the middle of the base with its tip at the end of the axis.
+ * On the side u goes around the axis and v up it, the base is a disk.
+ */
+pub struct Cone
+{
+    base: Point3,
+    basis: Onb, // w along the axis
+    height: f32,
+    radius: f32,
+    cap: Disk,
+    material: Rc<dyn Material>,
+    material_id: Cell<usize> // Given when the scene is built
+}
+
+impl Cone
+{
+    pub fn new(base: Point3, axis: Vec3, radius: f32, material: Rc<dyn Material>) -> Cone
+    {
+        Cone
+        {
+            base,
+            basis: Onb::new(&axis),
+            height: axis.length(),
+            radius,
+            cap: Disk::new(base, axis.negate_vec(), radius, material.clone()),
+            material_id: Cell::new(0),
+            material
+        }
+    }
+
+    fn hit_side(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        // The side is x^2 + y^2 = (k (height - z))^2 around the axis, where k is the radius over the height
+        let o = self.basis.project(&(r.origin() - self.base));
+        let d = self.basis.project(&r.direction());
+        let k2 = (self.radius / self.height).powi(2);
+        let h = self.height - o.z();
+        let a = d.x()*d.x() + d.y()*d.y() - k2*d.z()*d.z();
+        let half_b = o.x()*d.x() + o.y()*d.y() + k2*h*d.z();
+        let c = o.x()*o.x() + o.y()*o.y() - k2*h*h;
+
+        // A ray parallel to the side of the cone crosses it only once
+        let roots = if a.abs() < PARALLEL_EPSILON {
+            [-c / (2.0*half_b), INFINITY]
+        } else {
+            let discriminant = half_b*half_b - a*c;
+            if discriminant < 0.0
+            {
+                return false;
+            }
+            let sqrtd = discriminant.sqrt();
+            let (t0, t1) = ((-half_b - sqrtd) / a, (-half_b + sqrtd) / a);
+            [t0.min(t1), t0.max(t1)]
+        };
+
+        for t in roots
+        {
+            let z = o.z() + t*d.z();
+            if !(t_min..=t_max).contains(&t) || !(0.0..=self.height).contains(&z)
+            {
+                continue;
+            }
+
+            // The gradient of the surface, which points out of the cone
+            let (x, y) = (o.x() + t*d.x(), o.y() + t*d.y());
+            let normal = self.basis.local(&Vec3::new(x, y, k2*(self.height - z))).unit_vector();
+            hit_rec.t = t;
+            hit_rec.p = r.at(t);
+            hit_rec.set_face_normal(r, &normal);
+            hit_rec.u = (y.atan2(x) + PI as f32) / (2.0 * PI as f32);
+            hit_rec.v = z / self.height;
+            hit_rec.setMaterial(self.material.clone());
+            hit_rec.material_id = self.material_id.get();
+            return true;
+        }
+        false
+    }
+}
+
+impl Hittable for Cone
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        let mut closest_so_far = t_max;
+        let mut hit_anything = false;
+        if self.hit_side(r, t_min, closest_so_far, hit_rec)
+        {
+            closest_so_far = hit_rec.t;
+            hit_anything = true;
+        }
+        if self.cap.hit(r, t_min, closest_so_far, hit_rec)
+        {
+            hit_anything = true;
+        }
+        hit_anything
+    }
+
+    fn bounding_box(&self) -> Option<Aabb>
+    {
+        let tip = self.base + self.basis.w().const_mul(self.height);
+        Some(self.cap.bounding_box()?.surrounding(&Aabb::new(tip, tip)))
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        self.material_id.set(ids.number(&self.material));
+        self.cap.number_materials(ids);
+    }
+}
@@ -0,0 +1,158 @@
+use std::rc::Rc;
+
+use crate::{vectors::ray::Ray, utility::rtweekend::INFINITY};
+
+use super::{aabb::Aabb, hittable::Hittable, hit_record::HitRecord, material::material::MaterialIds, span::{Crossing, Span}};
+
+/**
+ * How the insides of two objects are combined
+ */
+#[derive(Copy, Clone)]
+pub enum Operation
+{
+    Union, // Inside either object
+    Intersection, // Inside both objects
+    Difference // Inside the first object and not the second, which is carved out of it
+}
+
+impl Operation
+{
+    fn inside(&self, in_a: bool, in_b: bool) -> bool
+    {
+        match self
+        {
+            Operation::Union => in_a || in_b,
+            Operation::Intersection => in_a && in_b,
+            Operation::Difference => in_a && !in_b
+        }
+    }
+}
+
+/**
+ * A solid made of two closed objects, for lenses, hollow objects and the like. The spans the ray is inside
+ * each object are combined, and the surfaces where the ray goes in or out of the result are kept. Each
+ * surface keeps the material of the object it came from, with its front face turned to the outside of the
+ * result, so glass carved out of glass is entered and left the right way. Not sampled as a light.
+ */
+pub struct Csg
+{
+    operation: Operation,
+    a: Rc<dyn Hittable>,
+    b: Rc<dyn Hittable>
+}
+
+impl Csg
+{
+    pub fn new(operation: Operation, a: Rc<dyn Hittable>, b: Rc<dyn Hittable>) -> Csg
+    {
+        Csg
+        {
+            operation,
+            a,
+            b
+        }
+    }
+}
+
+impl Hittable for Csg
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        // Whether the ray starts inside is only known from following it all the way, so t_max can not cut it short
+        for span in self.spans(r, t_min)
+        {
+            if span.enter.t > t_max
+            {
+                break;
+            }
+            for crossing in [span.enter, span.exit]
+            {
+                if let Some(record) = crossing.record
+                {
+                    if crossing.t <= t_max
+                    {
+                        *hit_rec = record;
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    fn bounding_box(&self) -> Option<Aabb>
+    {
+        match self.operation
+        {
+            Operation::Union => match (self.a.bounding_box(), self.b.bounding_box()) {
+                (Some(a), Some(b)) => Some(a.surrounding(&b)),
+                _ => None
+            },
+            Operation::Intersection => self.a.bounding_box().or_else(|| self.b.bounding_box()),
+            Operation::Difference => self.a.bounding_box()
+        }
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        self.a.number_materials(ids);
+        self.b.number_materials(ids);
+    }
+
+    fn spans(&self, r: &Ray, t_min: f32) -> Vec<Span>
+    {
+        // Every crossing of either object, with whether it is of the first object and goes in
+        let mut crossings: Vec<(Crossing, bool, bool)> = Vec::new();
+        for (object, is_a) in [(&self.a, true), (&self.b, false)]
+        {
+            for span in object.spans(r, t_min)
+            {
+                crossings.push((span.enter, is_a, true));
+                crossings.push((span.exit, is_a, false));
+            }
+        }
+        crossings.sort_by(|x, y| x.0.t.total_cmp(&y.0.t));
+
+        let mut spans = Vec::new();
+        let (mut in_a, mut in_b) = (false, false);
+        let mut enter: Option<Crossing> = None;
+        for (crossing, is_a, entering) in crossings
+        {
+            let was_inside = self.operation.inside(in_a, in_b);
+            if is_a
+            {
+                in_a = entering;
+            } else {
+                in_b = entering;
+            }
+            let inside = self.operation.inside(in_a, in_b);
+
+            if inside && !was_inside
+            {
+                enter = Some(facing(crossing, true));
+            } else if was_inside && !inside {
+                if let Some(start) = enter.take()
+                {
+                    spans.push(Span { enter: start, exit: facing(crossing, false) });
+                }
+            }
+        }
+        if let Some(start) = enter
+        {
+            spans.push(Span { enter: start, exit: Crossing::cut(INFINITY) });
+        }
+        spans
+    }
+}
+
+/**
+ * Sets which way the ray crosses the surface of the result. The normal already points against the ray.
+ */
+fn facing(mut crossing: Crossing, entering: bool) -> Crossing
+{
+    if let Some(record) = &mut crossing.record
+    {
+        record.front_face = entering;
+    }
+    crossing
+}
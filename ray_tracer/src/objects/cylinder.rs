@@ -0,0 +1,115 @@
+use std::{cell::Cell, rc::Rc};
+
+use crate::{vectors::{onb::Onb, ray::Ray, vec3::{Point3, Vec3}}, utility::rtweekend::PI};
+
+use super::{aabb::Aabb, disk::Disk, hittable::Hittable, hit_record::HitRecord, material::material::{Material, MaterialIds}};
+
+/**
+ * A round cylinder closed at both ends, standing on the middle of its base and reaching along the axis
+ * to the middle of its top. On the side u goes around the axis and v up it, the caps are disks.
+ */
+pub struct Cylinder
+{
+    base: Point3,
+    basis: Onb, // w along the axis
+    height: f32,
+    radius: f32,
+    caps: [Disk; 2],
+    material: Rc<dyn Material>,
+    material_id: Cell<usize> // Given when the scene is built
+}
+
+impl Cylinder
+{
+    pub fn new(base: Point3, axis: Vec3, radius: f32, material: Rc<dyn Material>) -> Cylinder
+    {
+        let top = base + axis;
+
+        Cylinder
+        {
+            base,
+            basis: Onb::new(&axis),
+            height: axis.length(),
+            radius,
+            caps: [
+                Disk::new(base, axis.negate_vec(), radius, material.clone()),
+                Disk::new(top, axis, radius, material.clone())
+            ],
+            material_id: Cell::new(0),
+            material
+        }
+    }
+
+    fn hit_side(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        // The side is x^2 + y^2 = radius^2 around the axis, between the base and the top
+        let o = self.basis.project(&(r.origin() - self.base));
+        let d = self.basis.project(&r.direction());
+        let a = d.x()*d.x() + d.y()*d.y();
+        let half_b = o.x()*d.x() + o.y()*d.y();
+        let c = o.x()*o.x() + o.y()*o.y() - self.radius*self.radius;
+        let discriminant = half_b*half_b - a*c;
+        if a == 0.0 || discriminant < 0.0
+        {
+            return false;
+        }
+
+        let sqrtd = discriminant.sqrt();
+        for t in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a]
+        {
+            let z = o.z() + t*d.z();
+            if !(t_min..=t_max).contains(&t) || !(0.0..=self.height).contains(&z)
+            {
+                continue;
+            }
+
+            let local = Vec3::new(o.x() + t*d.x(), o.y() + t*d.y(), 0.0);
+            hit_rec.t = t;
+            hit_rec.p = r.at(t);
+            hit_rec.set_face_normal(r, &self.basis.local(&local).const_div(self.radius));
+            hit_rec.u = (local.y().atan2(local.x()) + PI as f32) / (2.0 * PI as f32);
+            hit_rec.v = z / self.height;
+            hit_rec.setMaterial(self.material.clone());
+            hit_rec.material_id = self.material_id.get();
+            return true;
+        }
+        false
+    }
+}
+
+impl Hittable for Cylinder
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        let mut closest_so_far = t_max;
+        let mut hit_anything = false;
+        if self.hit_side(r, t_min, closest_so_far, hit_rec)
+        {
+            closest_so_far = hit_rec.t;
+            hit_anything = true;
+        }
+        for cap in &self.caps
+        {
+            if cap.hit(r, t_min, closest_so_far, hit_rec)
+            {
+                closest_so_far = hit_rec.t;
+                hit_anything = true;
+            }
+        }
+        hit_anything
+    }
+
+    fn bounding_box(&self) -> Option<Aabb>
+    {
+        Some(self.caps[0].bounding_box()?.surrounding(&self.caps[1].bounding_box()?))
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        self.material_id.set(ids.number(&self.material));
+        for cap in &self.caps
+        {
+            cap.number_materials(ids);
+        }
+    }
+}
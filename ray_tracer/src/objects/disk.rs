@@ -0,0 +1,92 @@
+use std::{cell::Cell, rc::Rc};
+
+use crate::{vectors::{onb::Onb, ray::Ray, vec3::{Point3, Vec3}}, utility::rtweekend::PI};
+
+use super::{aabb::Aabb, hittable::Hittable, hit_record::HitRecord, material::material::{Material, MaterialIds}, plane::hit_plane};
+
+/**
+ * A flat round disk facing along its normal, or an annulus when it has a hole in the middle. On the disk
+ * u goes around the normal and v from the inner edge out.
+ */
+pub struct Disk
+{
+    center: Point3,
+    basis: Onb, // w along the normal
+    radius: f32,
+    inner_radius: f32,
+    material: Rc<dyn Material>,
+    material_id: Cell<usize> // Given when the scene is built
+}
+
+impl Disk
+{
+    pub fn new(center: Point3, normal: Vec3, radius: f32, material: Rc<dyn Material>) -> Disk
+    {
+        Disk
+        {
+            center,
+            basis: Onb::new(&normal),
+            radius,
+            inner_radius: 0.0,
+            material_id: Cell::new(0),
+            material
+        }
+    }
+
+    /**
+     * Cuts a round hole of the given radius out of the middle, which makes the disk an annulus
+     */
+    pub fn with_hole(self, inner_radius: f32) -> Disk
+    {
+        Disk
+        {
+            inner_radius: inner_radius.clamp(0.0, self.radius),
+            ..self
+        }
+    }
+}
+
+impl Hittable for Disk
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        let normal = self.basis.w();
+        let t = match hit_plane(r, &self.center, &normal, t_min, t_max) {
+            Some(t) => t,
+            None => return false
+        };
+
+        let p = r.at(t);
+        let local = self.basis.project(&(p - self.center));
+        let distance = (local.x()*local.x() + local.y()*local.y()).sqrt();
+        if distance > self.radius || distance < self.inner_radius
+        {
+            return false;
+        }
+
+        hit_rec.t = t;
+        hit_rec.p = p;
+        hit_rec.set_face_normal(r, &normal);
+        hit_rec.u = (local.y().atan2(local.x()) + PI as f32) / (2.0 * PI as f32);
+        hit_rec.v = if self.radius > self.inner_radius { (distance - self.inner_radius) / (self.radius - self.inner_radius) } else { 0.0 };
+        hit_rec.setMaterial(self.material.clone());
+        hit_rec.material_id = self.material_id.get();
+        true
+    }
+
+    /**
+     * Along each axis the disk reaches out by its radius times the sine of the angle between the axis and the normal
+     */
+    fn bounding_box(&self) -> Option<Aabb>
+    {
+        let n = self.basis.w();
+        let reach = |component: f32| self.radius * (1.0 - component*component).max(0.0).sqrt();
+        let extent = Vec3::new(reach(n.x()), reach(n.y()), reach(n.z()));
+        Some(Aabb::new(self.center - extent, self.center + extent).padded())
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        self.material_id.set(ids.number(&self.material));
+    }
+}
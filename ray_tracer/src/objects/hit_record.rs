@@ -1,70 +1,80 @@
-use std::rc::Rc;
-
-use crate::vectors::{vec3::{Point3, Vec3, dot}, ray::Ray};
-
-use super::material::{material::Material, lambertian::Lambertian};
-
-/**
- * A struct holding data about a hit on a Hittable object
- */
-#[derive(Clone)]
-pub struct HitRecord
-{
-    pub p: Point3, // Point of impact
-    pub normal: Vec3, // Normal
-    pub mat_ptr: Rc<dyn Material>,// Material which ray hit
-    pub t: f32, // Root
-    pub front_face: bool
-}
-
-/**
- * Deafult HitRecord
- */
-impl Default for HitRecord
-{
-    fn default() -> HitRecord
-    {
-        HitRecord { 
-            p: Point3::new(0.0,0.0,0.0), 
-            normal: Vec3::new(0.0,0.0,0.0), 
-            mat_ptr: Rc::new(Lambertian::default()),
-            t: 0.0, 
-            front_face: true 
-        }
-    }
-}
-
-/**
- * Public trait for a HitRecord
- */
-impl HitRecord
-{
-    // Checks which direction the normal should be pointed, based on the incoming ray.
-    pub fn set_face_normal(&mut self, r: &Ray, outward_normal: &Vec3) 
-    {
-        self.front_face = dot(&r.direction(), outward_normal) < 0.0;
-        self.normal = if self.front_face {
-            // Need to clone as we do not own outward_normal
-            outward_normal.clone()
-        } else {
-            // Do not need to copy as negate_vec() gives us new vec
-            outward_normal.negate_vec()
-        }
-    }
-
-    /**
-     * Returns a new clone of the material object
-     */
-    pub fn getMaterial(&self) -> Rc<dyn Material>
-    {
-        Rc::clone(&self.mat_ptr)
-    }
-
-    /**
-     * Sets a new material for the object
-     */
-    pub fn setMaterial(&mut self, material: Rc<dyn Material>) 
-    {
-        self.mat_ptr = material;
-    }
+use std::rc::Rc;
+
+use crate::vectors::{vec3::{Color, Point3, Vec3, dot}, ray::Ray};
+
+use super::material::{material::Material, lambertian::Lambertian};
+
+/**
+ * A struct holding data about a hit on a Hittable object
+ */
+#[derive(Clone)]
+pub struct HitRecord
+{
+    pub p: Point3, // Point of impact
+    pub normal: Vec3, // Normal
+    pub mat_ptr: Rc<dyn Material>,// Material which ray hit
+    pub t: f32, // Root
+    pub u: f32, // Surface coordinates of the point of impact, in [0,1]
+    pub v: f32,
+    pub front_face: bool,
+    pub material_id: usize, // Number of the material hit, given when the scene was built
+    pub object_id: usize, // Index of the object hit in the outermost list
+    pub weight: Color // Path weight picked up in media the ray crossed on the way to the hit
+}
+
+/**
+ * Deafult HitRecord
+ */
+impl Default for HitRecord
+{
+    fn default() -> HitRecord
+    {
+        HitRecord { 
+            p: Point3::new(0.0,0.0,0.0), 
+            normal: Vec3::new(0.0,0.0,0.0), 
+            mat_ptr: Rc::new(Lambertian::default()),
+            t: 0.0, 
+            u: 0.0,
+            v: 0.0,
+            front_face: true,
+            material_id: 0,
+            object_id: 0,
+            weight: Color::new(1.0,1.0,1.0)
+        }
+    }
+}
+
+/**
+ * Public trait for a HitRecord
+ */
+impl HitRecord
+{
+    // Checks which direction the normal should be pointed, based on the incoming ray.
+    pub fn set_face_normal(&mut self, r: &Ray, outward_normal: &Vec3) 
+    {
+        self.front_face = dot(&r.direction(), outward_normal) < 0.0;
+        self.normal = if self.front_face {
+            // Need to clone as we do not own outward_normal
+            outward_normal.clone()
+        } else {
+            // Do not need to copy as negate_vec() gives us new vec
+            outward_normal.negate_vec()
+        }
+    }
+
+    /**
+     * Returns a new clone of the material object
+     */
+    pub fn getMaterial(&self) -> Rc<dyn Material>
+    {
+        Rc::clone(&self.mat_ptr)
+    }
+
+    /**
+     * Sets a new material for the object
+     */
+    pub fn setMaterial(&mut self, material: Rc<dyn Material>) 
+    {
+        self.mat_ptr = material;
+    }
 }
\ No newline at end of file
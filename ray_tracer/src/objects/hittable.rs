@@ -1,11 +1,118 @@
-use crate::vectors::ray::Ray;
-
-use super::hit_record::HitRecord;
-
-/**
- * Public trait for a hittable object
- */
-pub trait Hittable 
-{
-    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool; 
-}
+use crate::{vectors::{ray::Ray, vec3::{Color, Point3, Vec3}}, utility::rtweekend::INFINITY};
+
+use super::{aabb::Aabb, hit_record::HitRecord, material::material::MaterialIds, medium::volume::Traversal, span::{Crossing, Span}};
+
+// Distance moved past a surface before looking for the next one along the ray
+const CROSSING_EPSILON: f32 = 1e-4;
+// Surfaces followed along a ray before giving up
+const MAX_CROSSINGS: usize = 64;
+
+/**
+ * Public trait for a hittable object
+ */
+pub trait Hittable 
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool; 
+
+    /**
+     * Returns the fraction of light that passes through the object along the ray between t_min and t_max.
+     * Solid objects block all light, participating media override this.
+     */
+    fn transmittance(&self, r: &Ray, t_min: f32, t_max: f32) -> Color
+    {
+        let mut rec = HitRecord::default();
+        if self.hit(r, t_min, t_max, &mut rec)
+        {
+            return Color::new(0.0,0.0,0.0);
+        }
+        Color::new(1.0,1.0,1.0)
+    }
+
+    /**
+     * Returns the solid angle pdf of random() choosing the direction from origin.
+     * Objects that can not be sampled as lights return zero.
+     */
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vec3) -> f32
+    {
+        0.0
+    }
+
+    /**
+     * Returns a random direction from origin towards the object
+     */
+    fn random(&self, _origin: &Point3) -> Vec3
+    {
+        Vec3::new(1.0,0.0,0.0)
+    }
+
+    /**
+     * Returns the box the object is inside of, None if it has no bounds or they are not known
+     */
+    fn bounding_box(&self) -> Option<Aabb>
+    {
+        None
+    }
+
+    /**
+     * Gives the materials of the object their numbers. Objects holding others pass the numbering on to them.
+     */
+    fn number_materials(&self, _ids: &mut MaterialIds)
+    {
+    }
+
+    /**
+     * Whether the object is a participating medium. Media are tracked after the surfaces along the ray are
+     * known, up to the nearest of them.
+     */
+    fn is_medium(&self) -> bool
+    {
+        false
+    }
+
+    /**
+     * Follows the ray through a participating medium from t_min up to t_max. Surfaces return None.
+     */
+    fn traverse(&self, _r: &Ray, _t_min: f32, _t_max: f32) -> Option<Traversal>
+    {
+        None
+    }
+
+    /**
+     * Returns the spans of the ray from t_min on that are inside the object, in order. The ray is followed
+     * from surface to surface, going in where it hits the front face and out where it hits the back, which
+     * only makes sense for closed objects. A ray that first comes out started inside, and one that goes
+     * in for good stays inside, like below a plane.
+     */
+    fn spans(&self, r: &Ray, t_min: f32) -> Vec<Span>
+    {
+        let mut spans = Vec::new();
+        let mut enter: Option<Crossing> = None;
+        let mut t = t_min;
+        for _ in 0..MAX_CROSSINGS
+        {
+            let mut rec = HitRecord::default();
+            if !self.hit(r, t, INFINITY, &mut rec)
+            {
+                break;
+            }
+            t = rec.t + CROSSING_EPSILON;
+
+            // Surfaces that do not match the side the ray is on are skipped
+            if rec.front_face
+            {
+                if enter.is_none()
+                {
+                    enter = Some(Crossing::surface(rec));
+                }
+            } else if enter.is_some() || spans.is_empty() {
+                let start = enter.take().unwrap_or(Crossing::cut(t_min));
+                spans.push(Span { enter: start, exit: Crossing::surface(rec) });
+            }
+        }
+        if let Some(start) = enter
+        {
+            spans.push(Span { enter: start, exit: Crossing::cut(INFINITY) });
+        }
+        spans
+    }
+}
@@ -1,59 +1,198 @@
-use std::vec::Vec;
-use std::rc::Rc;
-
-use crate::vectors::ray::Ray;
-
-use super::hit_record::HitRecord;
-use super::hittable::Hittable;
-
-/**
- * Struct for a list of hittable objects
- */
-pub struct HittableList
-{
-    list: Vec<Rc<dyn Hittable>>
-}
-
-impl HittableList
-{
-    pub fn new() -> HittableList
-    {
-        HittableList
-        {
-            list: Vec::<Rc<dyn Hittable>>::new()
-        }
-    }
-
-    pub fn clear(&mut self)
-    {
-        self.list.clear();
-    }
-
-    pub fn add(&mut self, object: Rc<dyn Hittable>)
-    {
-        self.list.push(object)
-    }
-
-    pub fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
-    {
-        let mut temp_rec = HitRecord::default();
-        let mut hit_anything = false;
-        let mut closest_so_far = t_max;
-
-        for object in self.list.clone()
-        {
-            if object.hit(r, t_min, closest_so_far, &mut temp_rec)
-            {
-                hit_anything = true;
-                // If ray hit object it can not hit another object thta has a higher root as that would mean we would hit a object that is unreachable.
-                closest_so_far = temp_rec.t;
-                // Ownership transfers, so we needed to implement copy trait for HitRecord -- OLD
-                // Owenrship still transfers but we now do a clone
-                // Copy occurs here
-                *hit_rec = temp_rec.clone();
-            }
-        }
-
-        return hit_anything;
-    }
-}
+use std::vec::Vec;
+use std::rc::Rc;
+
+use crate::utility::{counters::count_intersection_test, rtweekend::random_number};
+use crate::vectors::{ray::Ray, vec3::{Color, Point3, Vec3}};
+
+use super::aabb::Aabb;
+use super::hit_record::HitRecord;
+use super::hittable::Hittable;
+use super::material::material::MaterialIds;
+use super::medium::volume::Traversal;
+
+/**
+ * Struct for a list of hittable objects
+ */
+pub struct HittableList
+{
+    list: Vec<Rc<dyn Hittable>>,
+    bounds: Option<Aabb>, // Around the objects that have bounds, None if none of them do
+    unbounded: Vec<usize> // Indices of the objects without bounds, like planes, which every ray is tested against
+}
+
+impl HittableList
+{
+    pub fn new() -> HittableList
+    {
+        HittableList
+        {
+            list: Vec::<Rc<dyn Hittable>>::new(),
+            bounds: None,
+            unbounded: Vec::new()
+        }
+    }
+
+    pub fn clear(&mut self)
+    {
+        self.list.clear();
+        self.bounds = None;
+        self.unbounded.clear();
+    }
+
+    pub fn add(&mut self, object: Rc<dyn Hittable>)
+    {
+        match (self.bounds, object.bounding_box()) {
+            (Some(bounds), Some(other)) => self.bounds = Some(bounds.surrounding(&other)),
+            (None, Some(other)) => self.bounds = Some(other),
+            (_, None) => self.unbounded.push(self.list.len())
+        }
+        self.list.push(object)
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.list.is_empty()
+    }
+}
+
+impl Hittable for HittableList
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        // A ray that misses the box around the bounded objects can only hit the unbounded ones
+        let missed = self.bounds.is_some_and(|bounds| bounds.hit(r, t_min, t_max).is_none());
+        if missed && self.unbounded.is_empty()
+        {
+            return false;
+        }
+        let objects = self.list.iter().enumerate().filter(|(index, _)| !missed || self.unbounded.contains(index));
+
+        let mut temp_rec = HitRecord::default();
+        let mut hit_anything = false;
+        let mut closest_so_far = t_max;
+
+        // Lists that hold media count as surfaces, their media are weighed inside them
+        for (index, object) in objects.clone().filter(|(_, object)| !object.is_medium())
+        {
+            count_intersection_test();
+            // Only media put a weight on the record, so a surface must not keep one from an earlier hit
+            temp_rec.weight = Color::new(1.0,1.0,1.0);
+            if object.hit(r, t_min, closest_so_far, &mut temp_rec)
+            {
+                hit_anything = true;
+                // Lists inside this one set their own index first, so the outermost list's index is kept
+                temp_rec.object_id = index;
+                // If ray hit object it can not hit another object thta has a higher root as that would mean we would hit a object that is unreachable.
+                closest_so_far = temp_rec.t;
+                // Ownership transfers, so we needed to implement copy trait for HitRecord -- OLD
+                // Owenrship still transfers but we now do a clone
+                // Copy occurs here
+                *hit_rec = temp_rec.clone();
+            }
+        }
+
+        // Media are tracked up to the nearest surface
+        let traversals: Vec<(usize, Traversal)> = objects
+            .filter(|(_, object)| object.is_medium())
+            .filter_map(|(index, object)| {
+                count_intersection_test();
+                object.traverse(r, t_min, closest_so_far).map(|traversal| (index, traversal))
+            })
+            .collect();
+        if traversals.is_empty()
+        {
+            return hit_anything;
+        }
+
+        // The path goes on from the nearest scattering in any medium, else from the nearest surface, else from
+        // where the ray first leaves a medium
+        let scatter = nearest(traversals.iter().filter_map(|(index, traversal)| traversal.scatter.as_ref().map(|rec| (*index, rec))));
+        let exit = nearest(traversals.iter().filter_map(|(index, traversal)| traversal.exit.as_ref().map(|rec| (*index, rec))));
+        let leaves_medium = match (scatter, hit_anything, exit) {
+            (Some((index, rec)), _, _) => {
+                *hit_rec = rec.clone();
+                hit_rec.object_id = index;
+                false
+            }
+            (None, true, _) => false,
+            (None, false, Some((index, rec))) => {
+                *hit_rec = rec.clone();
+                hit_rec.object_id = index;
+                true
+            }
+            (None, false, None) => return false
+        };
+
+        // Every medium weighs the path with its null collisions up to where it goes on from
+        let weight = traversals.iter().fold(Color::new(1.0,1.0,1.0), |weight, (_, traversal)| weight * traversal.tracking.weight(hit_rec.t));
+        if leaves_medium && (weight - Color::new(1.0,1.0,1.0)).near_zero()
+        {
+            // Nothing was picked up, the ray goes on as if the media were not there
+            return false;
+        }
+        hit_rec.weight = hit_rec.weight * weight;
+        true
+    }
+
+    /**
+     * Light has to pass through every object in the list
+     */
+    fn transmittance(&self, r: &Ray, t_min: f32, t_max: f32) -> Color
+    {
+        let mut transmittance = Color::new(1.0,1.0,1.0);
+        for object in &self.list
+        {
+            transmittance = transmittance * object.transmittance(r, t_min, t_max);
+            if transmittance.max_component() <= 0.0
+            {
+                break;
+            }
+        }
+        transmittance
+    }
+
+    /**
+     * The list samples each of its objects with equal probability
+     */
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32
+    {
+        if self.list.is_empty()
+        {
+            return 0.0;
+        }
+
+        let weight = 1.0 / self.list.len() as f32;
+        self.list.iter().map(|object| weight * object.pdf_value(origin, direction)).sum()
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3
+    {
+        let index = ((random_number() * self.list.len() as f32) as usize).min(self.list.len() - 1);
+        self.list[index].random(origin)
+    }
+
+    /**
+     * None if any object in the list has no bounds
+     */
+    fn bounding_box(&self) -> Option<Aabb>
+    {
+        if self.unbounded.is_empty() { self.bounds } else { None }
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        for object in &self.list
+        {
+            object.number_materials(ids);
+        }
+    }
+}
+
+/**
+ * Returns the record with the smallest t, with the index of the object it belongs to
+ */
+fn nearest<'a, I>(records: I) -> Option<(usize, &'a HitRecord)>
+    where I: Iterator<Item = (usize, &'a HitRecord)>
+{
+    records.min_by(|a, b| a.1.t.total_cmp(&b.1.t))
+}
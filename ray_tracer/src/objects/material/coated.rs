@@ -0,0 +1,153 @@
+use std::rc::Rc;
+
+use crate::{vectors::{ray::Ray, spectrum::channel_wavelengths, vec3::{Color, dot, reflect}}, objects::hit_record::HitRecord, utility::rtweekend::random_number};
+
+use super::{material::Material, scatter_record::ScatterRecord, fresnel::{dielectric, thin_film}};
+
+/**
+ * The layer on top of a coated material
+ */
+#[derive(Copy, Clone)]
+pub enum Coating
+{
+    Clear { ior: f32 }, // Smooth clear varnish, like the top coat of car paint
+    ThinFilm { thickness: f32, ior: f32, substrate_ior: f32 } // Film of the given thickness in nm, with interference colors
+}
+
+/**
+ * A smooth coating over a base material. Light is either reflected by the coating, or passes through
+ * it to the base material, losing what the coating reflects on the way in and out. The coating only
+ * covers the front face.
+ */
+#[derive(Clone)]
+pub struct Coated
+{
+    pub base: Rc<dyn Material>,
+    pub coating: Coating
+}
+
+impl Coated
+{
+    /**
+     * Covers the base with a clear coat with the given index of refraction
+     */
+    pub fn clear_coat(base: Rc<dyn Material>, ior: f32) -> Coated
+    {
+        Coated
+        {
+            base,
+            coating: Coating::Clear { ior }
+        }
+    }
+
+    /**
+     * Covers the base with a thin film. The substrate index of refraction is that of the material below the
+     * film, 1.0 for a soap bubble.
+     */
+    pub fn thin_film(base: Rc<dyn Material>, thickness: f32, ior: f32, substrate_ior: f32) -> Coated
+    {
+        Coated
+        {
+            base,
+            coating: Coating::ThinFilm { thickness, ior, substrate_ior }
+        }
+    }
+
+    /**
+     * Reflectance of the coating for the channels of the ray, at the given cosine to the normal
+     */
+    fn reflectance(&self, r: &Ray, cosine: f32) -> Color
+    {
+        match self.coating
+        {
+            Coating::Clear { ior } => {
+                let f = dielectric(cosine, ior);
+                Color::new(f, f, f)
+            }
+            Coating::ThinFilm { thickness, ior, substrate_ior } => {
+                let lambda = channel_wavelengths(r);
+                Color::new(
+                    thin_film(cosine, thickness, ior, substrate_ior, lambda.x()),
+                    thin_film(cosine, thickness, ior, substrate_ior, lambda.y()),
+                    thin_film(cosine, thickness, ior, substrate_ior, lambda.z()))
+            }
+        }
+    }
+
+    /**
+     * Probability of reflecting off the coating rather than going on to the base material
+     */
+    fn reflect_probability(&self, r: &Ray, cos_o: f32) -> f32
+    {
+        self.reflectance(r, cos_o).average().clamp(0.05, 0.95)
+    }
+}
+
+impl Material for Coated
+{
+    fn scatter(&self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord) -> bool
+    {
+        if !rec.front_face
+        {
+            return self.base.scatter(r_in, rec, srec);
+        }
+
+        let unit_direction = r_in.direction().unit_vector();
+        let cos_o = dot(&unit_direction.negate_vec(), &rec.normal);
+        let reflectance = self.reflectance(r_in, cos_o);
+        let p = self.reflect_probability(r_in, cos_o);
+
+        if random_number() < p
+        {
+            srec.specular_ray = Ray::new(rec.p, reflect(&unit_direction, &rec.normal))
+                .with_wavelength(r_in.wavelength())
+                .with_spectrum(r_in.spectrum());
+            srec.attenuation = reflectance.const_div(p);
+            srec.pdf = None;
+            return true;
+        }
+
+        if !self.base.scatter(r_in, rec, srec)
+        {
+            return false;
+        }
+        if srec.pdf.is_none()
+        {
+            // A specular base is not evaluated through bsdf(), so the light lost to the coating goes here
+            let transmitted = Color::new(1.0,1.0,1.0) - reflectance;
+            srec.attenuation = (srec.attenuation * transmitted).const_div(1.0 - p);
+        }
+        true
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color
+    {
+        self.base.emitted(r_in, rec)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f32
+    {
+        self.base.scattering_pdf(r_in, rec, scattered)
+    }
+
+    fn bsdf(&self, r_in: &Ray, rec: &HitRecord, srec: &ScatterRecord, scattered: &Ray) -> Color
+    {
+        let base = self.base.bsdf(r_in, rec, srec, scattered);
+        if !rec.front_face
+        {
+            return base;
+        }
+
+        // Light passes through the coating on the way in and again on the way out. The base is only
+        // reached with probability 1 - p, which is compensated here.
+        let cos_o = dot(&r_in.direction().unit_vector().negate_vec(), &rec.normal);
+        let cos_i = dot(&scattered.direction().unit_vector(), &rec.normal);
+        let white = Color::new(1.0,1.0,1.0);
+        let transmitted = (white - self.reflectance(r_in, cos_o)) * (white - self.reflectance(r_in, cos_i));
+
+        (base * transmitted).const_div(1.0 - self.reflect_probability(r_in, cos_o))
+    }
+}
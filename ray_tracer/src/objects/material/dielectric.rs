@@ -1,76 +1,182 @@
-use crate::{vectors::{ray::Ray, vec3::{Color, refract, dot, reflect, Vec3}}, objects::hit_record::HitRecord, utility::rtweekend::random_number};
-
-use super::material::Material;
-
-#[derive(Copy, Clone)]
-
-pub struct Dielectric
-{
-    pub ir: f32
-}
-
-impl Default for Dielectric
-{
-    fn default() -> Self {
-        Dielectric { 
-            ir: 1.0 // Index of Refraction
-         }
-    }
-}
-
-impl Dielectric
-{
-    pub fn new(index_of_refraction: f32) -> Dielectric
-    {
-        Dielectric
-        {
-            ir: index_of_refraction
-        }
-    }
-}
-
-impl Material for Dielectric
-{
-    fn scatter(&self, 
-        r_in: &Ray, 
-        rec: &HitRecord, 
-        attenuation: &mut Color, 
-        scattered: &mut Ray) -> bool 
-    {
-        *attenuation = Color::new(1.0,1.0,1.0);
-        let refraction_ratio = if rec.front_face {
-            1.0/self.ir
-        } else {
-            self.ir
-        };
-        
-        let unit_direction = r_in.direction().unit_vector();
-        let cos_theta = dot(&unit_direction.negate_vec(), &rec.normal).min(1.0);
-        let sin_theta = (1.0 - cos_theta*cos_theta).sqrt();
-
-        // Check if ray can refract from object
-        let cannot_refract = refraction_ratio * sin_theta > 1.0 ;
-        let direction: Vec3;
-        if cannot_refract || reflectance(cos_theta, refraction_ratio) > random_number() {
-            // Must Reflect
-            direction = reflect(&unit_direction, &rec.normal);
-        }else {
-            // Can Refract
-            direction = refract(&unit_direction, &rec.normal, refraction_ratio);
-        }
-
-        *scattered = Ray::new(rec.p, direction);
-        return true
-    }
-}
-
-/**
- * Check angle of impact for ray, to figure out if Dielectric material should reflect or not
- */
-fn reflectance(cosine: f32, ref_idx: f32) -> f32
-{
-    // Use Schlick's approximation for reflectance.
-    let mut r0 = (1.0-ref_idx) / (1.0+ref_idx);
-    r0 = r0*r0;
-    r0 + (1.0-r0)*((1.0-cosine).powf(5.0))
+use crate::{vectors::{ray::Ray, vec3::{Color, refract, dot, reflect, Vec3}, color::RGB_WAVELENGTHS, spectrum::sample_color}, objects::hit_record::HitRecord, utility::rtweekend::random_number};
+
+use super::{material::Material, scatter_record::ScatterRecord};
+
+/**
+ * How the index of refraction varies with wavelength, with the wavelength in micrometers
+ */
+#[derive(Copy, Clone)]
+pub enum Dispersion
+{
+    None,
+    Cauchy { a: f32, b: f32 }, // n = a + b / wavelength^2
+    Sellmeier { b: [f32; 3], c: [f32; 3] } // n^2 = 1 + sum of b_i wavelength^2 / (wavelength^2 - c_i)
+}
+
+#[derive(Copy, Clone)]
+
+pub struct Dielectric
+{
+    pub ir: f32,
+    pub dispersion: Dispersion,
+    pub absorption: Color // Absorption coefficient per unit length inside the object
+}
+
+impl Default for Dielectric
+{
+    fn default() -> Self {
+        Dielectric { 
+            ir: 1.0, // Index of Refraction
+            dispersion: Dispersion::None,
+            absorption: Color::new(0.0,0.0,0.0)
+         }
+    }
+}
+
+impl Dielectric
+{
+    pub fn new(index_of_refraction: f32) -> Dielectric
+    {
+        Dielectric
+        {
+            ir: index_of_refraction,
+            dispersion: Dispersion::None,
+            absorption: Color::new(0.0,0.0,0.0)
+        }
+    }
+
+    /**
+     * Returns a copy with a wavelength dependent index of refraction
+     */
+    pub fn with_dispersion(self, dispersion: Dispersion) -> Dielectric
+    {
+        Dielectric
+        {
+            dispersion,
+            ..self
+        }
+    }
+
+    /**
+     * Returns a copy that absorbs light inside it by the Beer-Lambert law. The distance absorbed over is the
+     * length of the ray that comes out of the object, so it is only right for rays that went in through its
+     * surface. A path that starts or bounces off something inside the object, like a medium or another object
+     * held in it, is absorbed from that point on and not over the part before it.
+     */
+    pub fn with_absorption(self, absorption: Color) -> Dielectric
+    {
+        Dielectric
+        {
+            absorption,
+            ..self
+        }
+    }
+
+    /**
+     * Returns a copy tinted so that light keeps the given fraction of each channel after travelling distance inside it
+     */
+    pub fn with_tint(self, tint: Color, distance: f32) -> Dielectric
+    {
+        let absorption = Color::new(
+            -tint.x().max(1e-6).ln() / distance,
+            -tint.y().max(1e-6).ln() / distance,
+            -tint.z().max(1e-6).ln() / distance);
+        self.with_absorption(absorption)
+    }
+
+    /**
+     * Returns the index of refraction at the given wavelength in nm, or ir if the ray has not been split up
+     */
+    pub fn index_of_refraction(&self, wavelength: Option<f32>) -> f32
+    {
+        let lambda = match wavelength {
+            Some(nm) => nm / 1000.0,
+            None => return self.ir
+        };
+        let lambda2 = lambda*lambda;
+
+        match self.dispersion
+        {
+            Dispersion::None => self.ir,
+            Dispersion::Cauchy { a, b } => a + b / lambda2,
+            Dispersion::Sellmeier { b, c } => {
+                let sum: f32 = (0..3).map(|i| b[i] * lambda2 / (lambda2 - c[i])).sum();
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+}
+
+impl Material for Dielectric
+{
+    fn scatter(&self, 
+        r_in: &Ray, 
+        rec: &HitRecord, 
+        srec: &mut ScatterRecord) -> bool 
+    {
+        srec.attenuation = Color::new(1.0,1.0,1.0);
+        srec.pdf = None;
+
+        // A dispersive material splits the ray up, by letting it continue in a single channel.
+        // A spectral ray keeps its hero wavelength, in the first channel.
+        let mut wavelength = r_in.wavelength();
+        if wavelength.is_none() && !matches!(self.dispersion, Dispersion::None)
+        {
+            let (channel, lambda) = match r_in.spectrum() {
+                Some(spectrum) => (0, spectrum.x()),
+                None => {
+                    let channel = ((random_number() * 3.0) as usize).min(2);
+                    (channel, RGB_WAVELENGTHS[channel])
+                }
+            };
+            wavelength = Some(lambda);
+            let mut weight = [0.0; 3];
+            weight[channel] = 3.0;
+            srec.attenuation = Color::new(weight[0], weight[1], weight[2]);
+        }
+
+        // Hitting the inside of the surface means the ray has travelled through the object, from where it
+        // started. That is the surface it came in through unless something inside the object scattered it.
+        if !rec.front_face
+        {
+            let distance = rec.t * r_in.direction().length();
+            srec.attenuation = srec.attenuation * sample_color(&self.absorption, r_in).const_mul(-distance).exp();
+        }
+
+        let ir = self.index_of_refraction(wavelength);
+        let refraction_ratio = if rec.front_face {
+            1.0/ir
+        } else {
+            ir
+        };
+        
+        let unit_direction = r_in.direction().unit_vector();
+        let cos_theta = dot(&unit_direction.negate_vec(), &rec.normal).min(1.0);
+        let sin_theta = (1.0 - cos_theta*cos_theta).sqrt();
+
+        // Check if ray can refract from object
+        let cannot_refract = refraction_ratio * sin_theta > 1.0 ;
+        let direction: Vec3;
+        if cannot_refract || reflectance(cos_theta, refraction_ratio) > random_number() {
+            // Must Reflect
+            direction = reflect(&unit_direction, &rec.normal);
+        }else {
+            // Can Refract
+            direction = refract(&unit_direction, &rec.normal, refraction_ratio);
+        }
+
+        srec.specular_ray = Ray::new(rec.p, direction).with_wavelength(wavelength).with_spectrum(r_in.spectrum());
+        return true
+    }
+}
+
+/**
+ * Check angle of impact for ray, to figure out if Dielectric material should reflect or not
+ */
+fn reflectance(cosine: f32, ref_idx: f32) -> f32
+{
+    // Use Schlick's approximation for reflectance.
+    let mut r0 = (1.0-ref_idx) / (1.0+ref_idx);
+    r0 = r0*r0;
+    r0 + (1.0-r0)*((1.0-cosine).powf(5.0))
 }
\ No newline at end of file
@@ -0,0 +1,43 @@
+use crate::{vectors::{ray::Ray, spectrum::sample_color, vec3::Color}, objects::hit_record::HitRecord};
+
+use super::{material::Material, scatter_record::ScatterRecord};
+
+/**
+ * An emissive material, which gives off light from its front face and does not scatter
+ */
+#[derive(Copy, Clone)]
+pub struct DiffuseLight
+{
+    pub emit: Color
+}
+
+impl DiffuseLight
+{
+    pub fn new(emit: Color) -> DiffuseLight
+    {
+        DiffuseLight
+        {
+            emit
+        }
+    }
+}
+
+impl Material for DiffuseLight
+{
+    fn scatter(&self,
+        _r_in: &Ray,
+        _rec: &HitRecord,
+        _srec: &mut ScatterRecord) -> bool
+    {
+        false
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color
+    {
+        if rec.front_face
+        {
+            return sample_color(&self.emit, r_in);
+        }
+        Color::new(0.0,0.0,0.0)
+    }
+}
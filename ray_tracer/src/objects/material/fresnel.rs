@@ -0,0 +1,100 @@
+use crate::{vectors::vec3::Color, utility::rtweekend::PI};
+
+/**
+ * How the reflectance of a surface changes with the angle of incidence
+ */
+#[derive(Copy, Clone)]
+pub enum Fresnel
+{
+    Schlick, // Schlick's approximation from the reflectance at normal incidence
+    Conductor { eta: Color, k: Color } // Exact reflectance of a conductor with complex index of refraction eta + ik per channel
+}
+
+/**
+ * Schlick's approximation, given the reflectance f0 at normal incidence
+ */
+pub fn schlick(cosine: f32, f0: Color) -> Color
+{
+    let weight = (1.0 - cosine.clamp(0.0, 1.0)).powf(5.0);
+    f0 + (Color::new(1.0,1.0,1.0) - f0).const_mul(weight)
+}
+
+/**
+ * Unpolarised reflectance of a conductor, computed per channel
+ */
+pub fn conductor(cosine: f32, eta: Color, k: Color) -> Color
+{
+    Color::new(
+        conductor_channel(cosine, eta.x(), k.x()),
+        conductor_channel(cosine, eta.y(), k.y()),
+        conductor_channel(cosine, eta.z(), k.z()))
+}
+
+fn conductor_channel(cosine: f32, eta: f32, k: f32) -> f32
+{
+    let cos2 = cosine.clamp(0.0, 1.0).powi(2);
+    let sin2 = 1.0 - cos2;
+    let eta2 = eta*eta;
+    let k2 = k*k;
+
+    let t0 = eta2 - k2 - sin2;
+    let a2_plus_b2 = (t0*t0 + 4.0*eta2*k2).sqrt();
+    let t1 = a2_plus_b2 + cos2;
+    let a = (0.5 * (a2_plus_b2 + t0)).max(0.0).sqrt();
+    let t2 = 2.0 * cosine * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + sin2*sin2;
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+
+    (0.5 * (rp + rs)).clamp(0.0, 1.0)
+}
+
+/**
+ * Unpolarised reflectance of a smooth dielectric boundary with relative index of refraction eta
+ */
+pub fn dielectric(cosine: f32, eta: f32) -> f32
+{
+    let cos_i = cosine.clamp(0.0, 1.0);
+    let sin2_t = (1.0 - cos_i*cos_i) / (eta*eta);
+    if sin2_t >= 1.0
+    {
+        return 1.0; // Total internal reflection
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+
+    let rs = (cos_i - eta*cos_t) / (cos_i + eta*cos_t);
+    let rp = (eta*cos_i - cos_t) / (eta*cos_i + cos_t);
+    0.5 * (rs*rs + rp*rp)
+}
+
+/**
+ * Reflectance of a thin film of the given thickness (nm) and index of refraction on top of a substrate,
+ * seen from air at the given wavelength (nm). Light reflected at the top and bottom of the film interferes,
+ * which is summed over all internal reflections with the Airy formula for both polarisations.
+ */
+pub fn thin_film(cosine: f32, thickness: f32, film_ior: f32, substrate_ior: f32, wavelength: f32) -> f32
+{
+    let cos0 = cosine.clamp(0.0, 1.0);
+    let sin2_0 = 1.0 - cos0*cos0;
+    let cos1 = (1.0 - sin2_0 / (film_ior*film_ior)).max(0.0).sqrt();
+    let cos2 = (1.0 - sin2_0 / (substrate_ior*substrate_ior)).max(0.0).sqrt();
+
+    // Phase difference between the two reflected waves
+    let delta = 4.0 * PI as f32 * film_ior * thickness * cos1 / wavelength;
+
+    let airy = |r01: f32, r12: f32| {
+        let interference = 2.0 * r01 * r12 * delta.cos();
+        (r01*r01 + r12*r12 + interference) / (1.0 + r01*r01*r12*r12 + interference)
+    };
+
+    let s = airy(
+        (cos0 - film_ior*cos1) / (cos0 + film_ior*cos1),
+        (film_ior*cos1 - substrate_ior*cos2) / (film_ior*cos1 + substrate_ior*cos2));
+    let p = airy(
+        (film_ior*cos0 - cos1) / (film_ior*cos0 + cos1),
+        (substrate_ior*cos1 - film_ior*cos2) / (substrate_ior*cos1 + film_ior*cos2));
+
+    (0.5 * (s + p)).clamp(0.0, 1.0)
+}
@@ -0,0 +1,46 @@
+use std::rc::Rc;
+
+use crate::{vectors::{ray::Ray, vec3::{Color, dot}}, objects::hit_record::HitRecord, pdf::phase_pdf::{PhasePdf, henyey_greenstein}};
+
+use super::{material::Material, scatter_record::ScatterRecord};
+
+/**
+ * Phase function for scattering inside a participating medium. The asymmetry g is in (-1,1),
+ * where g > 0 favours forward scattering, g < 0 backward scattering and g = 0 is isotropic.
+ */
+#[derive(Copy, Clone)]
+pub struct HenyeyGreenstein
+{
+    pub albedo: Color,
+    pub g: f32
+}
+
+impl HenyeyGreenstein
+{
+    pub fn new(a: Color, g: f32) -> HenyeyGreenstein
+    {
+        HenyeyGreenstein
+        {
+            albedo: a,
+            g: g.clamp(-0.99, 0.99)
+        }
+    }
+}
+
+impl Material for HenyeyGreenstein
+{
+    fn scatter(&self,
+        r_in: &Ray,
+        _rec: &HitRecord,
+        srec: &mut ScatterRecord) -> bool
+    {
+        srec.attenuation = self.albedo;
+        srec.pdf = Some(Rc::new(PhasePdf::new(&r_in.direction(), self.g)));
+        true
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, _rec: &HitRecord, scattered: &Ray) -> f32
+    {
+        henyey_greenstein(dot(&r_in.direction().unit_vector(), &scattered.direction().unit_vector()), self.g)
+    }
+}
@@ -1,52 +1,51 @@
-use crate::{vectors::{vec3::{Color, Vec3, random_unit_vector}, ray::Ray}, objects::hit_record::HitRecord};
-
-use super::material::Material;
-
-#[derive(Copy, Clone)]
-
-pub struct Lambertian
-{
-    pub albedo: Color
-}
-
-impl Default for Lambertian
-{
-    fn default() -> Self {
-        Lambertian { 
-            albedo: Color::default()
-         }
-    }
-}
-
-impl Lambertian
-{
-    pub fn new(a: Color) -> Lambertian
-    {
-        Lambertian
-        {
-            albedo: a
-        }
-    }
-}
-
-impl Material for Lambertian
-{
-    fn scatter(&self, 
-        r_in: &Ray, 
-        rec: &HitRecord, 
-        attenuation: &mut Color, 
-        scattered: &mut Ray) -> bool 
-    {
-        let mut scatter_driection = rec.normal + random_unit_vector();   
-
-        // Catch degenerate scatter direction
-        if scatter_driection.near_zero()
-        {
-            scatter_driection = rec.normal;
-        }
-
-        *scattered = Ray::new(rec.p, scatter_driection);
-        *attenuation = self.albedo;
-        return true
-    }
+use std::rc::Rc;
+
+use crate::{vectors::{vec3::{Color, dot}, ray::Ray, spectrum::sample_color}, objects::hit_record::HitRecord, utility::rtweekend::PI, pdf::cosine_pdf::CosinePdf};
+
+use super::{material::Material, scatter_record::ScatterRecord};
+
+#[derive(Copy, Clone)]
+
+pub struct Lambertian
+{
+    pub albedo: Color
+}
+
+impl Default for Lambertian
+{
+    fn default() -> Self {
+        Lambertian { 
+            albedo: Color::default()
+         }
+    }
+}
+
+impl Lambertian
+{
+    pub fn new(a: Color) -> Lambertian
+    {
+        Lambertian
+        {
+            albedo: a
+        }
+    }
+}
+
+impl Material for Lambertian
+{
+    fn scatter(&self, 
+        r_in: &Ray, 
+        rec: &HitRecord, 
+        srec: &mut ScatterRecord) -> bool 
+    {
+        srec.attenuation = sample_color(&self.albedo, r_in);
+        srec.pdf = Some(Rc::new(CosinePdf::new(&rec.normal)));
+        true
+    }
+
+    fn scattering_pdf(&self, _r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f32
+    {
+        let cosine = dot(&rec.normal, &scattered.direction().unit_vector());
+        if cosine < 0.0 { 0.0 } else { cosine / PI as f32 }
+    }
 }
\ No newline at end of file
@@ -1,9 +1,92 @@
-use crate::{vectors::{ray::Ray, vec3::Color}, objects::hit_record::HitRecord};
-
-/**
- * Implementation for material
- */
-pub trait Material
-{
-    fn scatter(&self, r_in: &Ray, rec: &HitRecord, attenuation: &mut Color, scattered: &mut Ray) -> bool;
-} 
\ No newline at end of file
+use std::rc::Rc;
+
+use crate::{vectors::{ray::Ray, vec3::Color}, objects::hit_record::HitRecord};
+
+use super::scatter_record::ScatterRecord;
+
+/**
+ * Implementation for material
+ */
+pub trait Material
+{
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, srec: &mut ScatterRecord) -> bool;
+
+    /**
+     * Returns the light emitted from the hit point towards the incoming ray, no light by default
+     */
+    fn emitted(&self, _r_in: &Ray, _rec: &HitRecord) -> Color
+    {
+        Color::new(0.0,0.0,0.0)
+    }
+
+    /**
+     * Returns the scattering pdf for the scattered direction, defined so that attenuation * scattering_pdf
+     * is the BSDF times the cosine term. Only used for materials that give a pdf in scatter().
+     */
+    fn scattering_pdf(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f32
+    {
+        0.0
+    }
+
+    /**
+     * Returns the BSDF times the cosine term for scattering into the given direction. By default this is
+     * the attenuation times the scattering pdf, materials with a direction dependent color override it.
+     */
+    fn bsdf(&self, r_in: &Ray, rec: &HitRecord, srec: &ScatterRecord, scattered: &Ray) -> Color
+    {
+        srec.attenuation.const_mul(self.scattering_pdf(r_in, rec, scattered))
+    }
+
+    /**
+     * Returns the overall color of the surface, which guides the denoiser. By default this is the
+     * attenuation of the scatter.
+     */
+    fn albedo(&self, _r_in: &Ray, _rec: &HitRecord, srec: &ScatterRecord) -> Color
+    {
+        srec.attenuation
+    }
+
+    /**
+     * Returns true if the material only lets the ray continue in the same direction, like the edge of a medium
+     */
+    fn passes_through(&self) -> bool
+    {
+        false
+    }
+}
+
+/**
+ * Numbers the materials of a scene in the order its objects are walked in, which is the same for every frame
+ * and every process. Objects that share a material share its number.
+ */
+#[derive(Default)]
+pub struct MaterialIds
+{
+    materials: Vec<Option<Rc<dyn Material>>> // Materials by their number, None for numbers handed out without one
+}
+
+impl MaterialIds
+{
+    /**
+     * Returns the number of the material, giving it the next one if it has none yet
+     */
+    pub fn number(&mut self, material: &Rc<dyn Material>) -> usize
+    {
+        match self.materials.iter().position(|id| id.as_ref().is_some_and(|other| Rc::ptr_eq(other, material))) {
+            Some(id) => id,
+            None => {
+                self.materials.push(Some(material.clone()));
+                self.materials.len() - 1
+            }
+        }
+    }
+
+    /**
+     * Returns a new number for an object that makes up its materials as it is hit, like a medium
+     */
+    pub fn reserve(&mut self) -> usize
+    {
+        self.materials.push(None);
+        self.materials.len() - 1
+    }
+}
@@ -1,4 +1,10 @@
-pub mod material;
-pub mod lambertian;
-pub mod metal;
-pub mod dielectric;
\ No newline at end of file
+pub mod material;
+pub mod lambertian;
+pub mod metal;
+pub mod dielectric;
+pub mod henyey_greenstein;
+pub mod diffuse_light;
+pub mod scatter_record;
+pub mod fresnel;
+pub mod principled;
+pub mod coated;
\ No newline at end of file
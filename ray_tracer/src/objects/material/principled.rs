@@ -0,0 +1,180 @@
+use std::rc::Rc;
+
+use crate::{vectors::{ray::Ray, spectrum::sample_color, vec3::{Color, Vec3, dot}}, objects::hit_record::HitRecord, pdf::{pdf::Pdf, cosine_pdf::CosinePdf, ggx_pdf::{GgxPdf, ggx_d, smith_g1}}, utility::rtweekend::{random_number, PI}};
+
+use super::{material::Material, scatter_record::ScatterRecord, fresnel::{Fresnel, schlick, conductor}};
+
+/**
+ * A physically based material with a GGX microfacet specular lobe over a diffuse base.
+ * Metallic blends between a dielectric, which has a diffuse base and a grey specular reflectance of
+ * 0.08 * specular at normal incidence, and a metal which reflects its base color.
+ */
+#[derive(Copy, Clone)]
+pub struct Principled
+{
+    pub base_color: Color,
+    pub roughness: f32,
+    pub metallic: f32,
+    pub specular: f32,
+    pub fresnel: Fresnel
+}
+
+impl Principled
+{
+    pub fn new(base_color: Color, roughness: f32, metallic: f32, specular: f32) -> Principled
+    {
+        Principled
+        {
+            base_color,
+            roughness: roughness.clamp(0.0, 1.0),
+            metallic: metallic.clamp(0.0, 1.0),
+            specular: specular.clamp(0.0, 1.0),
+            fresnel: Fresnel::Schlick
+        }
+    }
+
+    /**
+     * Creates a metal from its complex index of refraction eta + ik per channel
+     */
+    pub fn conductor(eta: Color, k: Color, roughness: f32) -> Principled
+    {
+        Principled
+        {
+            base_color: Color::new(1.0,1.0,1.0),
+            roughness: roughness.clamp(0.0, 1.0),
+            metallic: 1.0,
+            specular: 0.5,
+            fresnel: Fresnel::Conductor { eta, k }
+        }
+    }
+
+    /**
+     * GGX alpha from the perceptual roughness
+     */
+    fn alpha(&self) -> f32
+    {
+        (self.roughness * self.roughness).max(1e-3)
+    }
+
+    /**
+     * Reflectance of the specular lobe for the given cosine between the light and the microfacet normal
+     */
+    fn fresnel(&self, r_in: &Ray, cosine: f32) -> Color
+    {
+        let dielectric_f0 = Color::new(1.0,1.0,1.0).const_mul(0.08 * self.specular);
+        let dielectric = schlick(cosine, dielectric_f0);
+        let metal = match self.fresnel {
+            Fresnel::Schlick => schlick(cosine, sample_color(&self.base_color, r_in)),
+            Fresnel::Conductor { eta, k } => conductor(cosine, sample_color(&eta, r_in), sample_color(&k, r_in))
+        };
+        dielectric.const_mul(1.0 - self.metallic) + metal.const_mul(self.metallic)
+    }
+
+    /**
+     * Probability of sampling the specular lobe rather than the diffuse one, seen from cos_o
+     */
+    fn specular_probability(&self, r_in: &Ray, cos_o: f32) -> f32
+    {
+        let specular = self.fresnel(r_in, cos_o).average();
+        let diffuse = (1.0 - self.metallic) * sample_color(&self.base_color, r_in).average() * (1.0 - specular);
+        if specular + diffuse <= 0.0
+        {
+            return 1.0;
+        }
+        (specular / (specular + diffuse)).clamp(0.1, 0.9)
+    }
+}
+
+impl Material for Principled
+{
+    fn scatter(&self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord) -> bool
+    {
+        let wo = r_in.direction().unit_vector().negate_vec();
+        let cos_o = dot(&wo, &rec.normal);
+        if cos_o <= 0.0
+        {
+            return false;
+        }
+
+        // The color depends on the direction, so it comes from bsdf() instead of the attenuation
+        srec.attenuation = Color::new(1.0,1.0,1.0);
+        srec.pdf = Some(Rc::new(PrincipledPdf {
+            diffuse: CosinePdf::new(&rec.normal),
+            specular: GgxPdf::new(&rec.normal, &wo, self.alpha()),
+            specular_probability: self.specular_probability(r_in, cos_o)
+        }));
+        true
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f32
+    {
+        let wo = r_in.direction().unit_vector().negate_vec();
+        let p = self.specular_probability(r_in, dot(&wo, &rec.normal));
+        let specular = GgxPdf::new(&rec.normal, &wo, self.alpha()).value(&scattered.direction());
+        let diffuse = CosinePdf::new(&rec.normal).value(&scattered.direction());
+        p * specular + (1.0 - p) * diffuse
+    }
+
+    fn albedo(&self, r_in: &Ray, _rec: &HitRecord, _srec: &ScatterRecord) -> Color
+    {
+        sample_color(&self.base_color, r_in)
+    }
+
+    fn bsdf(&self, r_in: &Ray, rec: &HitRecord, _srec: &ScatterRecord, scattered: &Ray) -> Color
+    {
+        let n = rec.normal;
+        let wo = r_in.direction().unit_vector().negate_vec();
+        let wi = scattered.direction().unit_vector();
+        let cos_o = dot(&wo, &n);
+        let cos_i = dot(&wi, &n);
+        if cos_o <= 0.0 || cos_i <= 0.0
+        {
+            return Color::new(0.0,0.0,0.0);
+        }
+
+        let h = (wo + wi).unit_vector();
+        let alpha = self.alpha();
+        let f = self.fresnel(r_in, dot(&wi, &h));
+
+        // D * G * F / (4 cos_o cos_i), times cos_i
+        let g = smith_g1(cos_o, alpha) * smith_g1(cos_i, alpha);
+        let specular = f.const_mul(ggx_d(dot(&h, &n), alpha) * g / (4.0 * cos_o));
+
+        // The diffuse base only gets the light the specular layer lets through
+        let transmitted = Color::new(1.0,1.0,1.0) - self.fresnel(r_in, cos_o);
+        let diffuse = (sample_color(&self.base_color, r_in) * transmitted).const_mul((1.0 - self.metallic) * cos_i / PI as f32);
+
+        specular + diffuse
+    }
+}
+
+/**
+ * Picks the specular lobe with the given probability, otherwise the diffuse lobe
+ */
+struct PrincipledPdf
+{
+    diffuse: CosinePdf,
+    specular: GgxPdf,
+    specular_probability: f32
+}
+
+impl Pdf for PrincipledPdf
+{
+    fn value(&self, direction: &Vec3) -> f32
+    {
+        self.specular_probability * self.specular.value(direction)
+            + (1.0 - self.specular_probability) * self.diffuse.value(direction)
+    }
+
+    fn generate(&self) -> Vec3
+    {
+        if random_number() < self.specular_probability
+        {
+            return self.specular.generate();
+        }
+        self.diffuse.generate()
+    }
+}
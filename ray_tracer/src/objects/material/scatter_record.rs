@@ -0,0 +1,15 @@
+use std::rc::Rc;
+
+use crate::{vectors::{ray::Ray, vec3::Color}, pdf::pdf::Pdf};
+
+/**
+ * The result of a material scattering a ray. Diffuse materials give a pdf to draw the scattered direction from,
+ * specular materials instead give the one ray they scatter into.
+ */
+#[derive(Default)]
+pub struct ScatterRecord
+{
+    pub attenuation: Color,
+    pub pdf: Option<Rc<dyn Pdf>>, // None if the material is specular
+    pub specular_ray: Ray // Only used if the material is specular
+}
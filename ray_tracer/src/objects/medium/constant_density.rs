@@ -0,0 +1,33 @@
+use crate::vectors::vec3::Point3;
+
+use super::density::Density;
+
+#[derive(Copy, Clone)]
+pub struct ConstantDensity
+{
+    pub density: f32
+}
+
+impl ConstantDensity
+{
+    pub fn new(density: f32) -> ConstantDensity
+    {
+        ConstantDensity
+        {
+            density
+        }
+    }
+}
+
+impl Density for ConstantDensity
+{
+    fn value(&self, _p: &Point3) -> f32
+    {
+        self.density
+    }
+
+    fn max_value(&self) -> f32
+    {
+        self.density
+    }
+}
@@ -0,0 +1,17 @@
+use crate::vectors::vec3::Point3;
+
+/**
+ * Implementation for a density field inside a participating medium
+ */
+pub trait Density
+{
+    /**
+     * Returns the density at the given point
+     */
+    fn value(&self, p: &Point3) -> f32;
+
+    /**
+     * Returns an upper bound of the density anywhere in the field, used as majorant when tracking
+     */
+    fn max_value(&self) -> f32;
+}
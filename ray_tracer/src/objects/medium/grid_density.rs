@@ -0,0 +1,112 @@
+use std::{fs, io};
+
+use crate::vectors::vec3::{Point3, Vec3};
+
+use super::density::Density;
+
+/**
+ * A voxel grid of densities spanning the box between min and max, sampled with trilinear interpolation
+ */
+pub struct GridDensity
+{
+    nx: usize,
+    ny: usize,
+    nz: usize,
+    values: Vec<f32>,
+    max_density: f32,
+    min: Point3,
+    extent: Vec3
+}
+
+impl GridDensity
+{
+    pub fn new(nx: usize, ny: usize, nz: usize, values: Vec<f32>, min: Point3, max: Point3) -> GridDensity
+    {
+        let max_density = values.iter().cloned().fold(0.0, f32::max);
+
+        GridDensity
+        {
+            nx,
+            ny,
+            nz,
+            values,
+            max_density,
+            min,
+            extent: max - min
+        }
+    }
+
+    /**
+     * Loads a voxel grid from a text file. The file starts with the resolution "nx ny nz"
+     * followed by nx*ny*nz whitespace separated densities, with x varying fastest.
+     */
+    pub fn load(path: &str, min: Point3, max: Point3) -> io::Result<GridDensity>
+    {
+        let contents = fs::read_to_string(path)?;
+        let mut tokens = contents.split_whitespace();
+        let mut next_value = || -> io::Result<f32> {
+            tokens.next()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "voxel grid ended early"))?
+                .parse::<f32>()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        };
+
+        let nx = next_value()? as usize;
+        let ny = next_value()? as usize;
+        let nz = next_value()? as usize;
+        if nx == 0 || ny == 0 || nz == 0
+        {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "voxel grid has an empty dimension"));
+        }
+
+        let mut values = Vec::with_capacity(nx * ny * nz);
+        for _ in 0..nx * ny * nz
+        {
+            values.push(next_value()?.max(0.0));
+        }
+
+        Ok(GridDensity::new(nx, ny, nz, values, min, max))
+    }
+
+    fn voxel(&self, x: usize, y: usize, z: usize) -> f32
+    {
+        self.values[x + self.nx * (y + self.ny * z)]
+    }
+}
+
+impl Density for GridDensity
+{
+    fn value(&self, p: &Point3) -> f32
+    {
+        // Map into voxel space, where voxel centers lie on integer coordinates
+        let local = *p - self.min;
+        let gx = local.x() / self.extent.x() * self.nx as f32 - 0.5;
+        let gy = local.y() / self.extent.y() * self.ny as f32 - 0.5;
+        let gz = local.z() / self.extent.z() * self.nz as f32 - 0.5;
+
+        // Outside the grid there is no medium
+        if gx < -0.5 || gy < -0.5 || gz < -0.5
+            || gx > self.nx as f32 - 0.5 || gy > self.ny as f32 - 0.5 || gz > self.nz as f32 - 0.5
+        {
+            return 0.0;
+        }
+
+        let clamp_index = |g: f32, n: usize| (g.floor().max(0.0) as usize).min(n - 1);
+        let (x0, y0, z0) = (clamp_index(gx, self.nx), clamp_index(gy, self.ny), clamp_index(gz, self.nz));
+        let (x1, y1, z1) = ((x0 + 1).min(self.nx - 1), (y0 + 1).min(self.ny - 1), (z0 + 1).min(self.nz - 1));
+        let (fx, fy, fz) = ((gx - x0 as f32).clamp(0.0, 1.0), (gy - y0 as f32).clamp(0.0, 1.0), (gz - z0 as f32).clamp(0.0, 1.0));
+
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        let c00 = lerp(self.voxel(x0, y0, z0), self.voxel(x1, y0, z0), fx);
+        let c10 = lerp(self.voxel(x0, y1, z0), self.voxel(x1, y1, z0), fx);
+        let c01 = lerp(self.voxel(x0, y0, z1), self.voxel(x1, y0, z1), fx);
+        let c11 = lerp(self.voxel(x0, y1, z1), self.voxel(x1, y1, z1), fx);
+
+        lerp(lerp(c00, c10, fy), lerp(c01, c11, fy), fz)
+    }
+
+    fn max_value(&self) -> f32
+    {
+        self.max_density
+    }
+}
@@ -0,0 +1,6 @@
+pub mod density;
+pub mod constant_density;
+pub mod noise_density;
+pub mod grid_density;
+pub mod volume;
+pub mod subsurface;
\ No newline at end of file
@@ -0,0 +1,40 @@
+use crate::{vectors::vec3::Point3, utility::perlin::Perlin};
+
+use super::density::Density;
+
+/**
+ * A procedural density field based on Perlin noise, varying between 0 and max_density
+ */
+pub struct NoiseDensity
+{
+    noise: Perlin,
+    scale: f32,
+    max_density: f32
+}
+
+impl NoiseDensity
+{
+    pub fn new(scale: f32, max_density: f32) -> NoiseDensity
+    {
+        NoiseDensity
+        {
+            noise: Perlin::new(),
+            scale,
+            max_density
+        }
+    }
+}
+
+impl Density for NoiseDensity
+{
+    fn value(&self, p: &Point3) -> f32
+    {
+        let n = 0.5 * (1.0 + self.noise.noise(&p.const_mul(self.scale)));
+        self.max_density * n.clamp(0.0, 1.0)
+    }
+
+    fn max_value(&self) -> f32
+    {
+        self.max_density
+    }
+}
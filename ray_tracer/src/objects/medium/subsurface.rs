@@ -0,0 +1,170 @@
+use std::{cell::Cell, rc::Rc};
+
+use crate::{vectors::{ray::Ray, spectrum::sample_color, vec3::{Color, dot, reflect, refract}}, objects::{aabb::Aabb, hittable::Hittable, hit_record::HitRecord, material::{material::{Material, MaterialIds}, scatter_record::ScatterRecord, fresnel::dielectric}}, pdf::{pdf::Pdf, cosine_pdf::CosinePdf, phase_pdf::PhasePdf}, utility::rtweekend::{random_number, INFINITY, PI}};
+
+use super::volume::delta_track;
+
+// Scattering events after which a random walk is given up on
+const MAX_WALK_STEPS: i32 = 1024;
+
+/**
+ * A closed object that light enters through a smooth dielectric surface and then scatters around
+ * inside, before leaving again somewhere else. This gives the soft look of skin, wax and marble.
+ * The inside is a homogeneous medium, given by its mean free path and single scattering albedo per channel,
+ * which scatters with a Henyey-Greenstein phase function.
+ */
+pub struct Subsurface
+{
+    boundary: Rc<dyn Hittable>,
+    material: Rc<RandomWalk>,
+    material_id: Cell<usize> // Given when the scene is built
+}
+
+impl Subsurface
+{
+    pub fn new(boundary: Rc<dyn Hittable>, mean_free_path: Color, albedo: Color, g: f32, ior: f32) -> Subsurface
+    {
+        let sigma_t = Color::new(1.0 / mean_free_path.x(), 1.0 / mean_free_path.y(), 1.0 / mean_free_path.z());
+        let sigma_s = sigma_t * albedo;
+
+        Subsurface
+        {
+            boundary: boundary.clone(),
+            material: Rc::new(RandomWalk
+            {
+                boundary,
+                sigma_a: sigma_t - sigma_s,
+                sigma_s,
+                g,
+                ior
+            }),
+            material_id: Cell::new(0)
+        }
+    }
+}
+
+impl Hittable for Subsurface
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        if !self.boundary.hit(r, t_min, t_max, hit_rec)
+        {
+            return false;
+        }
+        hit_rec.setMaterial(self.material.clone());
+        hit_rec.material_id = self.material_id.get();
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb>
+    {
+        self.boundary.bounding_box()
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        self.material_id.set(ids.reserve());
+    }
+}
+
+/**
+ * Surface material of a subsurface object, which follows the light on its walk through the inside
+ */
+struct RandomWalk
+{
+    boundary: Rc<dyn Hittable>,
+    sigma_a: Color,
+    sigma_s: Color,
+    g: f32,
+    ior: f32
+}
+
+impl Material for RandomWalk
+{
+    fn scatter(&self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord) -> bool
+    {
+        if !rec.front_face
+        {
+            // The end of a random walk, the light leaves diffusely through the surface
+            srec.attenuation = Color::new(1.0,1.0,1.0);
+            srec.pdf = Some(Rc::new(CosinePdf::new(&rec.normal.negate_vec())));
+            return true;
+        }
+        srec.pdf = None;
+
+        // Reflect off the surface, or refract into the object
+        let unit_direction = r_in.direction().unit_vector();
+        let cos_theta = dot(&unit_direction.negate_vec(), &rec.normal);
+        if random_number() < dielectric(cos_theta, self.ior)
+        {
+            srec.attenuation = Color::new(1.0,1.0,1.0);
+            srec.specular_ray = r_in.continued(rec.p, reflect(&unit_direction, &rec.normal));
+            return true;
+        }
+
+        let sigma_a = sample_color(&self.sigma_a, r_in);
+        let sigma_s = sample_color(&self.sigma_s, r_in);
+        let majorant = (sigma_a + sigma_s).max_component();
+
+        let mut throughput = Color::new(1.0,1.0,1.0);
+        let mut ray = r_in.continued(rec.p, refract(&unit_direction, &rec.normal, 1.0 / self.ior));
+        for _ in 0..MAX_WALK_STEPS
+        {
+            let mut exit = HitRecord::default();
+            if !self.boundary.hit(&ray, 0.0001, INFINITY, &mut exit)
+            {
+                // The ray started just outside the surface through numerical error, so it has already left
+                srec.attenuation = throughput;
+                srec.specular_ray = ray;
+                return true;
+            }
+
+            let tracking = delta_track(&ray, 0.0, exit.t, majorant, |_| (sigma_a, sigma_s));
+            if let Some((t, weight)) = tracking.scatter
+            {
+                throughput = throughput * tracking.weight(t) * weight;
+                if throughput.max_component() <= 0.0
+                {
+                    return false;
+                }
+
+                let direction = PhasePdf::new(&ray.direction().unit_vector(), self.g).generate();
+                ray = ray.continued(ray.at(t), direction);
+                continue;
+            }
+            throughput = throughput * tracking.weight(exit.t);
+
+            // Leave the object, unless the ray is reflected back inside. The ray is handed back
+            // before it reaches the surface, so the exit is scattered like any other hit.
+            let unit_direction = ray.direction().unit_vector();
+            let cos_theta = dot(&unit_direction.negate_vec(), &exit.normal);
+            if random_number() < dielectric(cos_theta, 1.0 / self.ior)
+            {
+                ray = ray.continued(exit.p, reflect(&unit_direction, &exit.normal));
+                continue;
+            }
+
+            srec.attenuation = throughput;
+            srec.specular_ray = ray;
+            return true;
+        }
+
+        false
+    }
+
+    fn albedo(&self, r_in: &Ray, _rec: &HitRecord, _srec: &ScatterRecord) -> Color
+    {
+        let sigma_s = sample_color(&self.sigma_s, r_in);
+        let sigma_t = sample_color(&self.sigma_a, r_in) + sigma_s;
+        Color::new(sigma_s.x() / sigma_t.x(), sigma_s.y() / sigma_t.y(), sigma_s.z() / sigma_t.z())
+    }
+
+    fn scattering_pdf(&self, _r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f32
+    {
+        let cosine = dot(&rec.normal.negate_vec(), &scattered.direction().unit_vector());
+        if cosine < 0.0 { 0.0 } else { cosine / PI as f32 }
+    }
+}
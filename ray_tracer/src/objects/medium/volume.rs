@@ -0,0 +1,310 @@
+use std::{cell::Cell, rc::Rc};
+
+use crate::{vectors::{ray::Ray, spectrum::sample_color, vec3::{Color, Point3, Vec3}}, objects::{aabb::Aabb, hittable::Hittable, hit_record::HitRecord, material::{material::{Material, MaterialIds}, henyey_greenstein::HenyeyGreenstein, scatter_record::ScatterRecord}}, utility::rtweekend::{random_number, INFINITY}};
+
+use super::density::Density;
+
+/**
+ * The collisions found by delta tracking a ray through a medium
+ */
+pub struct Tracking
+{
+    pub nulls: Vec<(f32, Color)>, // Null collisions in order, with the factor each puts on the path weight
+    pub scatter: Option<(f32, Color)> // Real scattering collision, with the factor it puts on the path weight
+}
+
+impl Tracking
+{
+    /**
+     * Returns the path weight picked up by null collisions before t
+     */
+    pub fn weight(&self, t: f32) -> Color
+    {
+        self.nulls.iter()
+            .take_while(|(t_null, _)| *t_null < t)
+            .fold(Color::new(1.0, 1.0, 1.0), |weight, (_, factor)| weight * *factor)
+    }
+}
+
+/**
+ * A ray followed through a participating medium. The path goes on from wherever the nearest thing along the
+ * ray is, and the medium weighs it with the null collisions before that point.
+ */
+pub struct Traversal
+{
+    pub tracking: Tracking,
+    pub scatter: Option<HitRecord>, // Where the ray scatters in the medium, the phase function holding the weight of that collision
+    pub exit: Option<HitRecord> // Where the ray leaves the medium, if that is within the tracked part of it
+}
+
+/**
+ * A heterogeneous participating medium filling a closed boundary. The medium has per channel
+ * absorption and scattering coefficients, scaled by a density field, and scatters light with a
+ * Henyey-Greenstein phase function. The boundary itself is invisible.
+ */
+pub struct Volume
+{
+    boundary: Rc<dyn Hittable>,
+    density: Rc<dyn Density>,
+    sigma_a: Color,
+    sigma_s: Color,
+    g: f32,
+    material_id: Cell<usize> // Stands for the phase function and the weights it hands out on each hit
+}
+
+impl Volume
+{
+    pub fn new(boundary: Rc<dyn Hittable>, density: Rc<dyn Density>, sigma_a: Color, sigma_s: Color, g: f32) -> Volume
+    {
+        Volume
+        {
+            boundary,
+            density,
+            sigma_a,
+            sigma_s,
+            g,
+            material_id: Cell::new(0)
+        }
+    }
+
+    /**
+     * Returns the absorption and scattering coefficients at the given point, in the channels the ray carries
+     */
+    fn coefficients(&self, r: &Ray, p: &Point3) -> (Color, Color)
+    {
+        let d = self.density.value(p);
+        (sample_color(&self.sigma_a, r).const_mul(d), sample_color(&self.sigma_s, r).const_mul(d))
+    }
+
+    /**
+     * Returns the largest extinction coefficient in the medium, over all channels the ray carries
+     */
+    fn majorant(&self, r: &Ray) -> f32
+    {
+        self.density.max_value() * (sample_color(&self.sigma_a, r) + sample_color(&self.sigma_s, r)).max_component()
+    }
+
+    /**
+     * Returns the part of [t_min, t_max] that lies inside the boundary, and whether it ends where the ray leaves the boundary
+     */
+    fn interval(&self, r: &Ray, t_min: f32, t_max: f32) -> Option<(f32, f32, bool)>
+    {
+        let mut rec1 = HitRecord::default();
+        let mut rec2 = HitRecord::default();
+
+        if !self.boundary.hit(r, -INFINITY, INFINITY, &mut rec1)
+        {
+            return None;
+        }
+        if !self.boundary.hit(r, rec1.t + 0.0001, INFINITY, &mut rec2)
+        {
+            return None;
+        }
+
+        let t0 = rec1.t.max(t_min).max(0.0);
+        let t1 = rec2.t.min(t_max);
+        if t0 >= t1
+        {
+            return None;
+        }
+
+        Some((t0, t1, rec2.t <= t_max))
+    }
+}
+
+impl Hittable for Volume
+{
+    /**
+     * On its own a medium applies its weight where the ray scatters or leaves it. Lists track media up to
+     * the nearest surface instead, see traverse.
+     */
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        let traversal = match self.traverse(r, t_min, t_max) {
+            Some(traversal) => traversal,
+            None => return false
+        };
+
+        if let Some(mut rec) = traversal.scatter
+        {
+            rec.weight = traversal.tracking.weight(rec.t);
+            *hit_rec = rec;
+            return true;
+        }
+
+        // Leaving the medium only matters if the ray picked up a weight on the way
+        match traversal.exit {
+            Some(mut rec) => {
+                rec.weight = traversal.tracking.weight(rec.t);
+                if (rec.weight - Color::new(1.0, 1.0, 1.0)).near_zero()
+                {
+                    return false;
+                }
+                *hit_rec = rec;
+                true
+            }
+            None => false
+        }
+    }
+
+    fn traverse(&self, r: &Ray, t_min: f32, t_max: f32) -> Option<Traversal>
+    {
+        let (t0, t1, leaves_boundary) = self.interval(r, t_min, t_max)?;
+        let tracking = delta_track(r, t0, t1, self.majorant(r), |p| self.coefficients(r, p));
+
+        let scatter = tracking.scatter.map(|(t, weight)| HitRecord {
+            p: r.at(t),
+            normal: Vec3::new(1.0, 0.0, 0.0), // Arbitrary, a medium has no surface
+            mat_ptr: Rc::new(HenyeyGreenstein::new(weight, self.g)),
+            t,
+            front_face: true,
+            material_id: self.material_id.get(),
+            ..HitRecord::default()
+        });
+        let exit = if leaves_boundary
+        {
+            Some(HitRecord {
+                p: r.at(t1),
+                normal: r.direction().unit_vector().negate_vec(),
+                mat_ptr: Rc::new(Transmitted {}),
+                t: t1,
+                front_face: true,
+                material_id: self.material_id.get(),
+                ..HitRecord::default()
+            })
+        } else {
+            None
+        };
+
+        Some(Traversal { tracking, scatter, exit })
+    }
+
+    /**
+     * Estimates transmittance with ratio tracking
+     */
+    fn transmittance(&self, r: &Ray, t_min: f32, t_max: f32) -> Color
+    {
+        let mut transmittance = Color::new(1.0, 1.0, 1.0);
+        let majorant = self.majorant(r);
+        let (t0, t1, _) = match self.interval(r, t_min, t_max) {
+            Some(interval) => interval,
+            None => return transmittance
+        };
+        if majorant <= 0.0
+        {
+            return transmittance;
+        }
+
+        let ray_length = r.direction().length();
+        let mut t = t0;
+        loop
+        {
+            t -= (1.0 - random_number()).ln() / (majorant * ray_length);
+            if t >= t1
+            {
+                return transmittance;
+            }
+
+            let (sigma_a, sigma_s) = self.coefficients(r, &r.at(t));
+            let sigma_t = sigma_a + sigma_s;
+            transmittance = transmittance * Color::new(
+                1.0 - sigma_t.x() / majorant,
+                1.0 - sigma_t.y() / majorant,
+                1.0 - sigma_t.z() / majorant);
+
+            if transmittance.max_component() <= 0.0
+            {
+                return Color::new(0.0, 0.0, 0.0);
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb>
+    {
+        self.boundary.bounding_box()
+    }
+
+    fn is_medium(&self) -> bool
+    {
+        true
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        self.material_id.set(ids.reserve());
+    }
+}
+
+/**
+ * Delta tracking through the interval [t0, t1] of the ray, with coefficients given per channel as (sigma_a, sigma_s).
+ * Collisions are classified as scattering or null with probabilities proportional to the weighted coefficients,
+ * and the path weight compensates so the estimate stays unbiased for chromatic media. Absorption never needs
+ * to be sampled, as an absorbed path carries no light. The factor of each null collision is kept, so the
+ * weight is known at any point along the ray.
+ */
+pub fn delta_track<F>(r: &Ray, t0: f32, t1: f32, majorant: f32, coefficients: F) -> Tracking
+    where F: Fn(&Point3) -> (Color, Color)
+{
+    let mut tracking = Tracking { nulls: Vec::new(), scatter: None };
+    if majorant <= 0.0
+    {
+        return tracking;
+    }
+
+    let ray_length = r.direction().length();
+    let mut weight = Color::new(1.0, 1.0, 1.0);
+    let mut t = t0;
+    loop
+    {
+        t -= (1.0 - random_number()).ln() / (majorant * ray_length);
+        if t >= t1
+        {
+            return tracking;
+        }
+
+        let (sigma_a, sigma_s) = coefficients(&r.at(t));
+        let sigma_n = Color::new(majorant, majorant, majorant) - sigma_a - sigma_s;
+
+        let p_s = (sigma_s * weight).average();
+        let p_n = (sigma_n * weight).average();
+        if p_s + p_n <= 0.0
+        {
+            // Everything that is left gets absorbed
+            tracking.scatter = Some((t, Color::new(0.0, 0.0, 0.0)));
+            return tracking;
+        }
+
+        if random_number() * (p_s + p_n) < p_s
+        {
+            tracking.scatter = Some((t, sigma_s.const_div(majorant * p_s / (p_s + p_n))));
+            return tracking;
+        }
+        let factor = sigma_n.const_div(majorant * p_n / (p_s + p_n));
+        weight = weight * factor;
+        tracking.nulls.push((t, factor));
+    }
+}
+
+/**
+ * Lets the ray continue unchanged. The record carries the weight it picked up inside the medium.
+ */
+struct Transmitted {}
+
+impl Material for Transmitted
+{
+    fn scatter(&self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord) -> bool
+    {
+        srec.specular_ray = Ray::new(rec.p, r_in.direction());
+        srec.attenuation = Color::new(1.0, 1.0, 1.0);
+        srec.pdf = None;
+        true
+    }
+
+    fn passes_through(&self) -> bool
+    {
+        true
+    }
+}
@@ -1,6 +1,17 @@
-pub mod hittable;
-pub mod sphere;
-pub mod hit_record;
-pub mod hittable_list;
-pub mod material;
-pub mod object;
\ No newline at end of file
+pub mod hittable;
+pub mod sphere;
+pub mod hit_record;
+pub mod hittable_list;
+pub mod material;
+pub mod object;
+pub mod medium;
+pub mod animated;
+pub mod aabb;
+pub mod disk;
+pub mod cylinder;
+pub mod cone;
+pub mod torus;
+pub mod plane;
+pub mod quad;
+pub mod span;
+pub mod csg;
\ No newline at end of file
@@ -0,0 +1,81 @@
+use std::{cell::Cell, rc::Rc};
+
+use crate::vectors::{onb::Onb, ray::Ray, vec3::{Point3, Vec3, dot}};
+
+use super::{hittable::Hittable, hit_record::HitRecord, material::material::{Material, MaterialIds}};
+
+// Rays closer to parallel with a plane than this miss it
+const PARALLEL_EPSILON: f32 = 1e-8;
+
+/**
+ * A flat surface through a point, without end. The surface coordinates repeat every unit along two directions
+ * in the plane. It has no bounds, so it can not be sampled as a light.
+ */
+pub struct Plane
+{
+    point: Point3,
+    basis: Onb, // w along the normal
+    material: Rc<dyn Material>,
+    material_id: Cell<usize> // Given when the scene is built
+}
+
+impl Plane
+{
+    pub fn new(point: Point3, normal: Vec3, material: Rc<dyn Material>) -> Plane
+    {
+        Plane
+        {
+            point,
+            basis: Onb::new(&normal),
+            material_id: Cell::new(0),
+            material
+        }
+    }
+}
+
+impl Hittable for Plane
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        let normal = self.basis.w();
+        let t = match hit_plane(r, &self.point, &normal, t_min, t_max) {
+            Some(t) => t,
+            None => return false
+        };
+
+        hit_rec.t = t;
+        hit_rec.p = r.at(t);
+        hit_rec.set_face_normal(r, &normal);
+        let local = self.basis.project(&(hit_rec.p - self.point));
+        hit_rec.u = local.x().rem_euclid(1.0);
+        hit_rec.v = local.y().rem_euclid(1.0);
+        hit_rec.setMaterial(self.material.clone());
+        hit_rec.material_id = self.material_id.get();
+        true
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        self.material_id.set(ids.number(&self.material));
+    }
+}
+
+/**
+ * Returns where the ray crosses the plane through the point with the given normal, None if it runs along
+ * the plane or crosses it outside [t_min, t_max]. Flat objects start from this.
+ */
+pub fn hit_plane(r: &Ray, point: &Point3, normal: &Vec3, t_min: f32, t_max: f32) -> Option<f32>
+{
+    let denominator = dot(&r.direction(), normal);
+    if denominator.abs() < PARALLEL_EPSILON
+    {
+        return None;
+    }
+
+    let t = dot(&(*point - r.origin()), normal) / denominator;
+    if t < t_min || t_max < t
+    {
+        return None;
+    }
+    Some(t)
+}
@@ -0,0 +1,109 @@
+use std::{cell::Cell, rc::Rc};
+
+use crate::{vectors::{ray::Ray, vec3::{Point3, Vec3, cross, dot}}, utility::rtweekend::{random_number, INFINITY}};
+
+use super::{aabb::Aabb, hittable::Hittable, hit_record::HitRecord, material::material::{Material, MaterialIds}, plane::hit_plane};
+
+/**
+ * A parallelogram from a corner along two edges, with u along the first edge and v along the second.
+ * It faces along the cross product of the edges, which is the side an emissive material lights.
+ * Directions towards it are sampled uniformly over its area.
+ */
+pub struct Quad
+{
+    corner: Point3,
+    u: Vec3,
+    v: Vec3,
+    normal: Vec3,
+    w: Vec3, // Normal over its squared length, which gives the coordinates of points along the edges
+    area: f32,
+    material: Rc<dyn Material>,
+    material_id: Cell<usize> // Given when the scene is built
+}
+
+impl Quad
+{
+    pub fn new(corner: Point3, u: Vec3, v: Vec3, material: Rc<dyn Material>) -> Quad
+    {
+        let n = cross(&u, &v);
+
+        Quad
+        {
+            corner,
+            u,
+            v,
+            normal: n.unit_vector(),
+            w: n.const_div(n.length_squared()),
+            area: n.length(),
+            material_id: Cell::new(0),
+            material
+        }
+    }
+}
+
+impl Hittable for Quad
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        let t = match hit_plane(r, &self.corner, &self.normal, t_min, t_max) {
+            Some(t) => t,
+            None => return false
+        };
+
+        // Coordinates of the point along the edges, both in [0,1] inside the quad
+        let p = r.at(t);
+        let offset = p - self.corner;
+        let alpha = dot(&self.w, &cross(&offset, &self.v));
+        let beta = dot(&self.w, &cross(&self.u, &offset));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta)
+        {
+            return false;
+        }
+
+        hit_rec.t = t;
+        hit_rec.p = p;
+        hit_rec.set_face_normal(r, &self.normal);
+        hit_rec.u = alpha;
+        hit_rec.v = beta;
+        hit_rec.setMaterial(self.material.clone());
+        hit_rec.material_id = self.material_id.get();
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb>
+    {
+        let corners = [self.corner, self.corner + self.u, self.corner + self.v, self.corner + self.u + self.v];
+        Some(Aabb::from_points(&corners).padded())
+    }
+
+    /**
+     * Converts the uniform pdf over the area to solid angle, by the squared distance over the cosine at the quad
+     */
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32
+    {
+        let mut rec = HitRecord::default();
+        if !self.hit(&Ray::new(*origin, *direction), 0.001, INFINITY, &mut rec)
+        {
+            return 0.0;
+        }
+
+        let distance_squared = rec.t * rec.t * direction.length_squared();
+        let cosine = dot(direction, &self.normal).abs() / direction.length();
+        if cosine <= 0.0
+        {
+            return 0.0;
+        }
+        distance_squared / (cosine * self.area)
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3
+    {
+        let p = self.corner + self.u.const_mul(random_number()) + self.v.const_mul(random_number());
+        p - *origin
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        self.material_id.set(ids.number(&self.material));
+    }
+}
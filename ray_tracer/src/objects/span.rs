@@ -0,0 +1,42 @@
+use super::hit_record::HitRecord;
+
+/**
+ * A stretch of a ray that is inside an object, from where it goes in to where it comes out
+ */
+#[derive(Clone)]
+pub struct Span
+{
+    pub enter: Crossing,
+    pub exit: Crossing
+}
+
+/**
+ * Where a ray crosses the surface of an object
+ */
+#[derive(Clone)]
+pub struct Crossing
+{
+    pub t: f32,
+    pub record: Option<HitRecord> // None where the span is cut off by the start or end of the ray, not a surface
+}
+
+impl Crossing
+{
+    pub fn surface(record: HitRecord) -> Crossing
+    {
+        Crossing
+        {
+            t: record.t,
+            record: Some(record)
+        }
+    }
+
+    pub fn cut(t: f32) -> Crossing
+    {
+        Crossing
+        {
+            t,
+            record: None
+        }
+    }
+}
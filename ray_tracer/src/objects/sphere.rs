@@ -1,70 +1,139 @@
-use std::rc::Rc;
-
-use crate::vectors::{vec3::{Point3, dot}, ray::Ray};
-
-use super::{hittable::Hittable, hit_record::HitRecord, material::{material::Material, self}};
-
-pub struct Sphere
-{
-    center: Point3,
-    radius: f32,
-    material: Rc<dyn Material>
-}
-
-impl Sphere
-{
-    pub fn new(cen: Point3, r: f32, material: Rc<dyn Material>) -> Sphere
-    {
-        Sphere {
-            radius: r,
-            center: cen,
-            material: material
-        }
-    }
-}
-
-// Sphere implements hittable trait, to check if rays it it
-impl Hittable for Sphere 
-{
-    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
-    {
-        let oc = r.origin() - self.center;
-        let a = r.direction().length_squared();
-        let half_b = dot(&oc, &r.direction());
-        let c = oc.length_squared() - (self.radius*self.radius);
-        let discriminant = half_b*half_b - a*c;
-        
-        // Check if ray hit the object
-        if discriminant < 0.0
-        {
-            return false
-        }
-
-        let sqrtd = discriminant.sqrt();
-
-        // Find the nearest root that lies in the acceptable range.
-        let mut root = (-half_b - sqrtd) / a;
-        if root < t_min || t_max < root
-        {
-            root = (-half_b + sqrtd) / a;
-            if root < t_min || t_max < root
-            {
-                return false
-            }
-        }
-
-        // Set the hit record for the object
-        hit_rec.t = root;
-        hit_rec.p = r.at(hit_rec.t);
-        
-        // Set correct direction on normal
-        let outward_normal = &(hit_rec.p - self.center).const_div(self.radius); 
-        hit_rec.set_face_normal(r, outward_normal);
-        
-        // Set which material ray hit
-        let material_clone = Rc::clone(&self.material);
-        hit_rec.setMaterial(material_clone);
-
-        return true
-    }
-}
+use std::{cell::Cell, rc::Rc};
+
+use crate::{vectors::{vec3::{Point3, Vec3, dot}, ray::Ray, onb::Onb}, utility::rtweekend::{random_number, INFINITY, PI}};
+
+use super::{aabb::Aabb, hittable::Hittable, hit_record::HitRecord, material::{material::{Material, MaterialIds}, self}};
+
+pub struct Sphere
+{
+    center: Point3,
+    radius: f32,
+    material: Rc<dyn Material>,
+    material_id: Cell<usize> // Given when the scene is built
+}
+
+impl Sphere
+{
+    pub fn new(cen: Point3, r: f32, material: Rc<dyn Material>) -> Sphere
+    {
+        Sphere {
+            radius: r,
+            center: cen,
+            material_id: Cell::new(0),
+            material: material
+        }
+    }
+}
+
+// Sphere implements hittable trait, to check if rays it it
+impl Hittable for Sphere 
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        let oc = r.origin() - self.center;
+        let a = r.direction().length_squared();
+        let half_b = dot(&oc, &r.direction());
+        let c = oc.length_squared() - (self.radius*self.radius);
+        let discriminant = half_b*half_b - a*c;
+        
+        // Check if ray hit the object
+        if discriminant < 0.0
+        {
+            return false
+        }
+
+        let sqrtd = discriminant.sqrt();
+
+        // Find the nearest root that lies in the acceptable range.
+        let mut root = (-half_b - sqrtd) / a;
+        if root < t_min || t_max < root
+        {
+            root = (-half_b + sqrtd) / a;
+            if root < t_min || t_max < root
+            {
+                return false
+            }
+        }
+
+        // Set the hit record for the object
+        hit_rec.t = root;
+        hit_rec.p = r.at(hit_rec.t);
+        
+        // Set correct direction on normal
+        let outward_normal = &(hit_rec.p - self.center).const_div(self.radius); 
+        hit_rec.set_face_normal(r, outward_normal);
+        (hit_rec.u, hit_rec.v) = sphere_uv(&(hit_rec.p - self.center).const_div(self.radius.abs()));
+        
+        // Set which material ray hit
+        let material_clone = Rc::clone(&self.material);
+        hit_rec.setMaterial(material_clone);
+        hit_rec.material_id = self.material_id.get();
+
+        return true
+    }
+
+    /**
+     * Directions are sampled uniformly inside the cone the sphere subtends from origin
+     */
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32
+    {
+        let mut rec = HitRecord::default();
+        if !self.hit(&Ray::new(*origin, *direction), 0.001, INFINITY, &mut rec)
+        {
+            return 0.0;
+        }
+
+        let radius_squared = self.radius*self.radius;
+        let distance_squared = (self.center - *origin).length_squared();
+        if distance_squared <= radius_squared
+        {
+            return 0.0;
+        }
+
+        // 1 - cos_theta_max, written to stay accurate for small or distant spheres
+        let sin2_theta_max = radius_squared/distance_squared;
+        let one_minus_cos_theta_max = sin2_theta_max / (1.0 + (1.0 - sin2_theta_max).sqrt());
+        let solid_angle = 2.0 * PI as f32 * one_minus_cos_theta_max;
+        1.0 / solid_angle
+    }
+
+    fn random(&self, origin: &Point3) -> Vec3
+    {
+        let direction = self.center - *origin;
+        let distance_squared = direction.length_squared();
+
+        let uvw = Onb::new(&direction);
+
+        let r1 = random_number();
+        let r2 = random_number();
+        let sin2_theta_max = (self.radius*self.radius/distance_squared).min(1.0);
+        let z = 1.0 - r2*sin2_theta_max / (1.0 + (1.0 - sin2_theta_max).sqrt());
+        let phi = 2.0 * PI as f32 * r1;
+        let sin_theta = (1.0 - z*z).max(0.0).sqrt();
+
+        uvw.local(&Vec3::new(phi.cos()*sin_theta, phi.sin()*sin_theta, z))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb>
+    {
+        let r = self.radius.abs();
+        let extent = Vec3::new(r, r, r);
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        self.material_id.set(ids.number(&self.material));
+    }
+}
+
+/**
+ * Returns the coordinates (u,v) of a point on the unit sphere. u goes around the y axis starting from -x,
+ * v goes from the bottom to the top.
+ */
+fn sphere_uv(p: &Point3) -> (f32, f32)
+{
+    let theta = (-p.y()).clamp(-1.0, 1.0).acos();
+    let phi = (-p.z()).atan2(p.x()) + PI as f32;
+    (phi / (2.0 * PI as f32), theta / PI as f32)
+}
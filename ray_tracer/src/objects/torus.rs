@@ -0,0 +1,126 @@
+use std::{cell::Cell, rc::Rc};
+
+use crate::{vectors::{onb::Onb, ray::Ray, vec3::{Point3, Vec3}}, utility::{polynomial::solve_quartic, rtweekend::PI}};
+
+use super::{aabb::Aabb, hittable::Hittable, hit_record::HitRecord, material::material::{Material, MaterialIds}};
+
+// Newton steps taken on each root of the quartic, which loses precision in its closed form
+const ROOT_POLISH_STEPS: i32 = 2;
+
+/**
+ * A ring shaped like a doughnut, the surface around a circle of the major radius at the given distance,
+ * the minor radius. The circle lies across the axis around the center. u goes around the axis and v
+ * around the tube, starting on its inside.
+ */
+pub struct Torus
+{
+    center: Point3,
+    basis: Onb, // w along the axis
+    major_radius: f32,
+    minor_radius: f32,
+    bounds: Aabb,
+    material: Rc<dyn Material>,
+    material_id: Cell<usize> // Given when the scene is built
+}
+
+impl Torus
+{
+    pub fn new(center: Point3, axis: Vec3, major_radius: f32, minor_radius: f32, material: Rc<dyn Material>) -> Torus
+    {
+        let basis = Onb::new(&axis);
+        let (outer, thickness) = (major_radius + minor_radius, minor_radius);
+        let corners: Vec<Point3> = [(-1.0, -1.0, -1.0), (1.0, -1.0, -1.0), (-1.0, 1.0, -1.0), (1.0, 1.0, -1.0),
+                                    (-1.0, -1.0, 1.0), (1.0, -1.0, 1.0), (-1.0, 1.0, 1.0), (1.0, 1.0, 1.0)]
+            .iter()
+            .map(|(x, y, z)| center + basis.local(&Vec3::new(x * outer, y * outer, z * thickness)))
+            .collect();
+
+        Torus
+        {
+            center,
+            basis,
+            major_radius,
+            minor_radius,
+            bounds: Aabb::from_points(&corners),
+            material_id: Cell::new(0),
+            material
+        }
+    }
+}
+
+impl Hittable for Torus
+{
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, hit_rec: &mut HitRecord) -> bool
+    {
+        // The quartic is solved from where the ray enters the bounding box, with a unit direction,
+        // which keeps its coefficients small enough for the closed form to stay accurate
+        let (t_enter, t_exit) = match self.bounds.hit(r, t_min, t_max) {
+            Some(interval) => interval,
+            None => return false
+        };
+        let start = self.basis.project(&(r.at(t_enter) - self.center));
+        let direction = self.basis.project(&r.direction());
+        let length = direction.length() as f64;
+        let (ox, oy, oz) = (start.x() as f64, start.y() as f64, start.z() as f64);
+        let (dx, dy, dz) = (direction.x() as f64 / length, direction.y() as f64 / length, direction.z() as f64 / length);
+
+        // (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + y^2) along p = o + s d, with |d| = 1
+        let major2 = (self.major_radius as f64).powi(2);
+        let n = ox*dx + oy*dy + oz*dz;
+        let k = ox*ox + oy*oy + oz*oz + major2 - (self.minor_radius as f64).powi(2);
+        let coefficients = [
+            k*k - 4.0*major2*(ox*ox + oy*oy),
+            4.0*n*k - 8.0*major2*(ox*dx + oy*dy),
+            4.0*n*n + 2.0*k - 4.0*major2*(dx*dx + dy*dy),
+            4.0*n,
+            1.0
+        ];
+        let value = |s: f64| (((coefficients[4]*s + coefficients[3])*s + coefficients[2])*s + coefficients[1])*s + coefficients[0];
+        let slope = |s: f64| ((4.0*coefficients[4]*s + 3.0*coefficients[3])*s + 2.0*coefficients[2])*s + coefficients[1];
+
+        let closest = solve_quartic(coefficients).into_iter()
+            .map(|mut s| {
+                for _ in 0..ROOT_POLISH_STEPS
+                {
+                    let derivative = slope(s);
+                    if derivative != 0.0
+                    {
+                        s -= value(s) / derivative;
+                    }
+                }
+                t_enter + (s / length) as f32
+            })
+            .filter(|t| (t_min..=t_exit).contains(t))
+            .min_by(|a, b| a.total_cmp(b));
+        let t = match closest {
+            Some(t) => t,
+            None => return false
+        };
+
+        // The gradient of the surface, which points out of the tube
+        let p = r.at(t);
+        let local = self.basis.project(&(p - self.center));
+        let sum = local.length_squared() - self.major_radius*self.major_radius - self.minor_radius*self.minor_radius;
+        let gradient = Vec3::new(local.x() * sum, local.y() * sum, local.z() * (sum + 2.0*self.major_radius*self.major_radius));
+        let ring_distance = (local.x()*local.x() + local.y()*local.y()).sqrt();
+
+        hit_rec.t = t;
+        hit_rec.p = p;
+        hit_rec.set_face_normal(r, &self.basis.local(&gradient).unit_vector());
+        hit_rec.u = (local.y().atan2(local.x()) + PI as f32) / (2.0 * PI as f32);
+        hit_rec.v = (local.z().atan2(self.major_radius - ring_distance) + PI as f32) / (2.0 * PI as f32);
+        hit_rec.setMaterial(self.material.clone());
+        hit_rec.material_id = self.material_id.get();
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb>
+    {
+        Some(self.bounds)
+    }
+
+    fn number_materials(&self, ids: &mut MaterialIds)
+    {
+        self.material_id.set(ids.number(&self.material));
+    }
+}
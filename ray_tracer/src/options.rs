@@ -0,0 +1,175 @@
+use crate::{film::{filter::Filter, region::Region}, vectors::debug::DebugMode};
+
+// Width of the terminal preview when the terminal does not tell its width
+const TERMINAL_COLUMNS: usize = 80;
+
+/**
+ * Settings for a render, read from the command line. Positional arguments name the scene and an
+ * optional argument to it, flags start with "--" and take values as "--flag=value".
+ */
+pub struct Options
+{
+    pub scene: Option<String>,
+    pub scene_arg: Option<String>,
+    pub spectral: bool, // Trace wavelengths instead of red, green and blue
+    pub frames: Option<u32>, // Render an animation of this many frames to numbered files
+    pub output: String, // Prefix of the numbered files
+    pub environment: Option<String>, // Image to light the scene with from all around
+    pub env_rotation: f32, // Degrees the environment is turned around the y axis
+    pub env_intensity: f32, // Scale of the environment's light
+    pub sky: bool, // Light the scene with daylight, unless an environment image is given
+    pub sun_elevation: f32, // Degrees above the horizon
+    pub sun_azimuth: f32, // Degrees around from -z towards +x
+    pub sun_size: f32, // Angular diameter in degrees
+    pub turbidity: f32, // Haziness of the sky, from 2 for clear to 10
+    pub ground_albedo: f32,
+    pub denoise: bool, // Filter the noise out of the image
+    pub keep_noisy: bool, // Also write the image before denoising, next to the output
+    pub aovs: bool, // Also write depth, normal, albedo, material and object buffers, next to the output
+    pub debug: Option<DebugMode>, // Show a debug view instead of the lighting
+    pub filter: Filter, // How samples are weighted into the pixels around them
+    pub region: Option<Region>, // Only render these pixels of the image
+    pub full_frame: bool, // Write a region as the full image, black outside it, instead of cropped
+    pub merge: Option<Vec<String>>, // Put these region images together instead of rendering
+    pub seed: u64, // Start of the random numbers, for the scene and each tile
+    pub coordinator: Option<String>, // Address to hand out tiles to workers on, instead of rendering here
+    pub worker: Option<String>, // Address of a coordinator to render tiles for
+    pub tile_timeout: u64, // Seconds a coordinator waits for a tile to come back before handing it to another worker
+    pub preview: bool, // Show the image in a window as it renders
+    pub terminal_preview: Option<usize>, // Show the image in the terminal as it renders, this many characters wide
+    pub args: Vec<String> // The arguments the options were read from
+}
+
+impl Options
+{
+    pub fn from_args() -> Options
+    {
+        Options::parse(std::env::args().skip(1).collect())
+    }
+
+    pub fn parse(args: Vec<String>) -> Options
+    {
+        let mut options = Options
+        {
+            scene: None,
+            scene_arg: None,
+            spectral: false,
+            frames: None,
+            output: String::from("frame"),
+            environment: None,
+            env_rotation: 0.0,
+            env_intensity: 1.0,
+            sky: false,
+            sun_elevation: 45.0,
+            sun_azimuth: 0.0,
+            sun_size: 0.53,
+            turbidity: 3.0,
+            ground_albedo: 0.3,
+            denoise: false,
+            keep_noisy: false,
+            aovs: false,
+            debug: None,
+            filter: Filter::Box,
+            region: None,
+            full_frame: false,
+            merge: None,
+            seed: 0,
+            coordinator: None,
+            worker: None,
+            tile_timeout: 600,
+            preview: false,
+            terminal_preview: None,
+            args: args.clone()
+        };
+
+        for arg in args
+        {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value)),
+                None => (arg.as_str(), None)
+            };
+
+            match (flag, value)
+            {
+                ("--spectral", None) => options.spectral = true,
+                ("--frames", Some(value)) => match value.parse::<u32>() {
+                    Ok(frames) if frames > 0 => options.frames = Some(frames),
+                    _ => eprintln!("Invalid frame count {}, rendering a single image", value)
+                },
+                ("--output", Some(value)) => options.output = String::from(value),
+                ("--environment", Some(value)) => options.environment = Some(String::from(value)),
+                ("--env-rotation", Some(value)) => match value.parse::<f32>() {
+                    Ok(degrees) => options.env_rotation = degrees,
+                    _ => eprintln!("Invalid environment rotation {}, ignored", value)
+                },
+                ("--env-intensity", Some(value)) => match value.parse::<f32>() {
+                    Ok(intensity) if intensity >= 0.0 => options.env_intensity = intensity,
+                    _ => eprintln!("Invalid environment intensity {}, ignored", value)
+                },
+                ("--sky", None) => options.sky = true,
+                ("--sun-elevation", Some(value)) => match value.parse::<f32>() {
+                    Ok(degrees) => options.sun_elevation = degrees,
+                    _ => eprintln!("Invalid sun elevation {}, ignored", value)
+                },
+                ("--sun-azimuth", Some(value)) => match value.parse::<f32>() {
+                    Ok(degrees) => options.sun_azimuth = degrees,
+                    _ => eprintln!("Invalid sun azimuth {}, ignored", value)
+                },
+                ("--sun-size", Some(value)) => match value.parse::<f32>() {
+                    Ok(degrees) if degrees > 0.0 => options.sun_size = degrees,
+                    _ => eprintln!("Invalid sun size {}, ignored", value)
+                },
+                ("--turbidity", Some(value)) => match value.parse::<f32>() {
+                    Ok(turbidity) if (1.7..=10.0).contains(&turbidity) => options.turbidity = turbidity,
+                    _ => eprintln!("Invalid turbidity {}, must be between 1.7 and 10", value)
+                },
+                ("--ground-albedo", Some(value)) => match value.parse::<f32>() {
+                    Ok(albedo) if (0.0..=1.0).contains(&albedo) => options.ground_albedo = albedo,
+                    _ => eprintln!("Invalid ground albedo {}, must be between 0 and 1", value)
+                },
+                ("--denoise", None) => options.denoise = true,
+                ("--keep-noisy", None) => options.keep_noisy = true,
+                ("--aovs", None) => options.aovs = true,
+                ("--filter", Some(value)) => match Filter::from_name(value) {
+                    Some(filter) => options.filter = filter,
+                    None => eprintln!("Unknown filter {}, expected box, tent, gaussian, mitchell or lanczos", value)
+                },
+                ("--region", Some(value)) => match Region::parse(value) {
+                    Some(region) => options.region = Some(region),
+                    None => eprintln!("Invalid region {}, expected x,y,width,height", value)
+                },
+                ("--full-frame", None) => options.full_frame = true,
+                ("--merge", Some(value)) => options.merge = Some(value.split(',').map(String::from).collect()),
+                ("--seed", Some(value)) => match value.parse::<u64>() {
+                    Ok(seed) => options.seed = seed,
+                    _ => eprintln!("Invalid seed {}, ignored", value)
+                },
+                ("--coordinator", Some(value)) => options.coordinator = Some(String::from(value)),
+                ("--worker", Some(value)) => options.worker = Some(String::from(value)),
+                ("--tile-timeout", Some(value)) => match value.parse::<u64>() {
+                    Ok(seconds) if seconds > 0 => options.tile_timeout = seconds,
+                    _ => eprintln!("Invalid tile timeout {}, ignored", value)
+                },
+                ("--preview", None) => options.preview = true,
+                ("--terminal-preview", None) => {
+                    let columns = std::env::var("COLUMNS").ok().and_then(|columns| columns.parse::<usize>().ok());
+                    options.terminal_preview = Some(columns.unwrap_or(TERMINAL_COLUMNS));
+                }
+                ("--terminal-preview", Some(value)) => match value.parse::<usize>() {
+                    Ok(columns) if columns > 0 => options.terminal_preview = Some(columns),
+                    _ => eprintln!("Invalid terminal preview width {}, ignored", value)
+                },
+                ("--debug", Some(value)) => match DebugMode::from_name(value) {
+                    Some(mode) => options.debug = Some(mode),
+                    None => eprintln!("Unknown debug view {}, expected normals, mask, uv, cost or path-depth", value)
+                },
+                _ if flag.starts_with("--") => eprintln!("Unknown option {}, ignored", arg),
+                _ if options.scene.is_none() => options.scene = Some(arg),
+                _ if options.scene_arg.is_none() => options.scene_arg = Some(arg),
+                _ => eprintln!("Unexpected argument {}, ignored", arg)
+            }
+        }
+
+        options
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{vectors::{vec3::{Vec3, dot, random_cosine_direction}, onb::Onb}, utility::rtweekend::PI};
+
+use super::pdf::Pdf;
+
+/**
+ * Cosine weighted directions over the hemisphere around a normal
+ */
+pub struct CosinePdf
+{
+    uvw: Onb
+}
+
+impl CosinePdf
+{
+    pub fn new(w: &Vec3) -> CosinePdf
+    {
+        CosinePdf
+        {
+            uvw: Onb::new(w)
+        }
+    }
+}
+
+impl Pdf for CosinePdf
+{
+    fn value(&self, direction: &Vec3) -> f32
+    {
+        let cosine_theta = dot(&direction.unit_vector(), &self.uvw.w());
+        (cosine_theta / PI as f32).max(0.0)
+    }
+
+    fn generate(&self) -> Vec3
+    {
+        self.uvw.local(&random_cosine_direction())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{vectors::{vec3::{Vec3, dot, cross}, onb::Onb}, utility::rtweekend::{random_number, PI}};
+
+use super::pdf::Pdf;
+
+/**
+ * Reflected directions from importance sampling the visible normals of a GGX microfacet distribution
+ */
+pub struct GgxPdf
+{
+    uvw: Onb,
+    wo: Vec3, // Direction towards the viewer, in basis coordinates
+    alpha: f32
+}
+
+impl GgxPdf
+{
+    /**
+     * Creates a pdf for the surface normal n, seen from the direction wo pointing away from the surface
+     */
+    pub fn new(n: &Vec3, wo: &Vec3, alpha: f32) -> GgxPdf
+    {
+        let uvw = Onb::new(n);
+        let w = wo.unit_vector();
+        let wo_local = Vec3::new(dot(&w, &uvw.u()), dot(&w, &uvw.v()), dot(&w, &uvw.w()));
+
+        GgxPdf
+        {
+            uvw,
+            wo: wo_local,
+            alpha
+        }
+    }
+}
+
+/**
+ * GGX normal distribution for a half vector with the given cosine to the normal
+ */
+pub fn ggx_d(cos_h: f32, alpha: f32) -> f32
+{
+    if cos_h <= 0.0
+    {
+        return 0.0;
+    }
+    let a2 = alpha*alpha;
+    let denom = cos_h*cos_h * (a2 - 1.0) + 1.0;
+    a2 / (PI as f32 * denom * denom)
+}
+
+/**
+ * Smith masking term for a direction with the given cosine to the normal
+ */
+pub fn smith_g1(cos_v: f32, alpha: f32) -> f32
+{
+    if cos_v <= 0.0
+    {
+        return 0.0;
+    }
+    let a2 = alpha*alpha;
+    2.0 * cos_v / (cos_v + (a2 + (1.0 - a2)*cos_v*cos_v).sqrt())
+}
+
+impl Pdf for GgxPdf
+{
+    fn value(&self, direction: &Vec3) -> f32
+    {
+        let n = self.uvw.w();
+        let wo = self.uvw.local(&self.wo);
+        let wi = direction.unit_vector();
+        let h = wo + wi;
+        if h.near_zero() || dot(&wi, &n) <= 0.0 || self.wo.z() <= 0.0
+        {
+            return 0.0;
+        }
+        let h = h.unit_vector();
+
+        // Visible normal density, divided by the Jacobian of reflecting about the half vector
+        smith_g1(self.wo.z(), self.alpha) * ggx_d(dot(&h, &n), self.alpha) / (4.0 * self.wo.z())
+    }
+
+    fn generate(&self) -> Vec3
+    {
+        // Heitz 2018, "Sampling the GGX Distribution of Visible Normals"
+        let vh = Vec3::new(self.alpha * self.wo.x(), self.alpha * self.wo.y(), self.wo.z()).unit_vector();
+        let lensq = vh.x()*vh.x() + vh.y()*vh.y();
+        let t1_axis = if lensq > 0.0 { Vec3::new(-vh.y(), vh.x(), 0.0).const_div(lensq.sqrt()) } else { Vec3::new(1.0, 0.0, 0.0) };
+        let t2_axis = cross(&vh, &t1_axis);
+
+        let r = random_number().sqrt();
+        let phi = 2.0 * PI as f32 * random_number();
+        let t1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z());
+        let t2 = (1.0 - s) * (1.0 - t1*t1).sqrt() + s * r * phi.sin();
+
+        let nh = t1_axis.const_mul(t1) + t2_axis.const_mul(t2) + vh.const_mul((1.0 - t1*t1 - t2*t2).max(0.0).sqrt());
+        let h = Vec3::new(self.alpha * nh.x(), self.alpha * nh.y(), nh.z().max(0.0)).unit_vector();
+
+        // Reflect the view direction about the sampled microfacet normal
+        let wi = h.const_mul(2.0 * dot(&self.wo, &h)) - self.wo;
+        self.uvw.local(&wi)
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{vectors::vec3::{Point3, Vec3}, objects::hittable::Hittable};
+
+use super::pdf::Pdf;
+
+/**
+ * Directions from an origin towards a hittable object, as sampled by the object itself
+ */
+pub struct HittablePdf<'a>
+{
+    objects: &'a dyn Hittable,
+    origin: Point3
+}
+
+impl<'a> HittablePdf<'a>
+{
+    pub fn new(objects: &'a dyn Hittable, origin: Point3) -> HittablePdf<'a>
+    {
+        HittablePdf
+        {
+            objects,
+            origin
+        }
+    }
+}
+
+impl Pdf for HittablePdf<'_>
+{
+    fn value(&self, direction: &Vec3) -> f32
+    {
+        self.objects.pdf_value(&self.origin, direction)
+    }
+
+    fn generate(&self) -> Vec3
+    {
+        self.objects.random(&self.origin)
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{vectors::vec3::{Point3, Vec3}, scene::Scene, utility::rtweekend::random_number};
+
+use super::{pdf::Pdf, hittable_pdf::HittablePdf};
+
+/**
+ * Directions from an origin towards everything in a scene that gives off light: the light objects
+ * and the environment. Each is picked with equal probability when generating.
+ */
+pub struct LightPdf<'a>
+{
+    scene: &'a Scene,
+    objects: Option<HittablePdf<'a>>
+}
+
+impl<'a> LightPdf<'a>
+{
+    pub fn new(scene: &'a Scene, origin: Point3) -> LightPdf<'a>
+    {
+        LightPdf
+        {
+            scene,
+            objects: if scene.lights.is_empty() { None } else { Some(HittablePdf::new(&scene.lights, origin)) }
+        }
+    }
+
+    /**
+     * Returns true if there is nothing to sample
+     */
+    pub fn is_empty(&self) -> bool
+    {
+        self.objects.is_none() && self.scene.environment.is_none()
+    }
+
+    fn sources(&self) -> f32
+    {
+        self.objects.is_some() as i32 as f32 + self.scene.environment.is_some() as i32 as f32
+    }
+}
+
+impl Pdf for LightPdf<'_>
+{
+    fn value(&self, direction: &Vec3) -> f32
+    {
+        if self.is_empty()
+        {
+            return 0.0;
+        }
+
+        let mut value = 0.0;
+        if let Some(objects) = &self.objects
+        {
+            value += objects.value(direction);
+        }
+        if let Some(environment) = &self.scene.environment
+        {
+            value += environment.pdf_value(direction);
+        }
+        value / self.sources()
+    }
+
+    fn generate(&self) -> Vec3
+    {
+        match (&self.objects, &self.scene.environment)
+        {
+            (Some(objects), Some(environment)) => {
+                if random_number() < 0.5 { objects.generate() } else { environment.random() }
+            }
+            (Some(objects), None) => objects.generate(),
+            (None, Some(environment)) => environment.random(),
+            (None, None) => Vec3::new(1.0, 0.0, 0.0)
+        }
+    }
+}
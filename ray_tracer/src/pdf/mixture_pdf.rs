@@ -0,0 +1,39 @@
+use crate::{vectors::vec3::Vec3, utility::rtweekend::random_number};
+
+use super::pdf::Pdf;
+
+/**
+ * Picks one of two pdfs with equal probability when generating, the density is the average of both
+ */
+pub struct MixturePdf<'a>
+{
+    p: [&'a dyn Pdf; 2]
+}
+
+impl<'a> MixturePdf<'a>
+{
+    pub fn new(p0: &'a dyn Pdf, p1: &'a dyn Pdf) -> MixturePdf<'a>
+    {
+        MixturePdf
+        {
+            p: [p0, p1]
+        }
+    }
+}
+
+impl Pdf for MixturePdf<'_>
+{
+    fn value(&self, direction: &Vec3) -> f32
+    {
+        0.5 * self.p[0].value(direction) + 0.5 * self.p[1].value(direction)
+    }
+
+    fn generate(&self) -> Vec3
+    {
+        if random_number() < 0.5
+        {
+            return self.p[0].generate();
+        }
+        self.p[1].generate()
+    }
+}
@@ -0,0 +1,7 @@
+pub mod pdf;
+pub mod cosine_pdf;
+pub mod hittable_pdf;
+pub mod mixture_pdf;
+pub mod phase_pdf;
+pub mod ggx_pdf;
+pub mod light_pdf;
\ No newline at end of file
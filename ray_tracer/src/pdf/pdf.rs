@@ -0,0 +1,17 @@
+use crate::vectors::vec3::Vec3;
+
+/**
+ * Implementation for a probability density function over directions
+ */
+pub trait Pdf
+{
+    /**
+     * Returns the solid angle density of generating the given direction
+     */
+    fn value(&self, direction: &Vec3) -> f32;
+
+    /**
+     * Returns a random direction distributed by the pdf
+     */
+    fn generate(&self) -> Vec3;
+}
@@ -0,0 +1,57 @@
+use crate::{vectors::{vec3::{Vec3, dot}, onb::Onb}, utility::rtweekend::{random_number, PI}};
+
+use super::pdf::Pdf;
+
+/**
+ * Directions distributed by the Henyey-Greenstein phase function around the direction of travel
+ */
+pub struct PhasePdf
+{
+    uvw: Onb,
+    g: f32
+}
+
+impl PhasePdf
+{
+    pub fn new(direction: &Vec3, g: f32) -> PhasePdf
+    {
+        PhasePdf
+        {
+            uvw: Onb::new(direction),
+            g
+        }
+    }
+}
+
+/**
+ * Returns the Henyey-Greenstein phase function for the cosine of the scattering angle
+ */
+pub fn henyey_greenstein(cos_theta: f32, g: f32) -> f32
+{
+    let denom = 1.0 + g*g - 2.0*g*cos_theta;
+    (1.0 - g*g) / (4.0 * PI as f32 * denom * denom.sqrt())
+}
+
+impl Pdf for PhasePdf
+{
+    fn value(&self, direction: &Vec3) -> f32
+    {
+        henyey_greenstein(dot(&direction.unit_vector(), &self.uvw.w()), self.g)
+    }
+
+    fn generate(&self) -> Vec3
+    {
+        let g = self.g;
+        let xi = random_number();
+        let cos_theta = if g.abs() < 1e-3 {
+            1.0 - 2.0*xi
+        } else {
+            let sqr_term = (1.0 - g*g) / (1.0 - g + 2.0*g*xi);
+            ((1.0 + g*g - sqr_term*sqr_term) / (2.0*g)).clamp(-1.0, 1.0)
+        };
+        let sin_theta = (1.0 - cos_theta*cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI as f32 * random_number();
+
+        self.uvw.local(&Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta))
+    }
+}
@@ -0,0 +1,3 @@
+#[cfg(feature = "preview")]
+pub mod window;
+pub mod terminal;
\ No newline at end of file
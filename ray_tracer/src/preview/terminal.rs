@@ -0,0 +1,84 @@
+use std::io::{self, Write};
+
+use crate::{utility::image::Image, vectors::{color::to_rgb8, vec3::Color}};
+
+/**
+ * A small copy of the image drawn in the terminal with 24-bit color, for a look at a render over SSH.
+ * Each character is the upper half block, colored as the top pixel with the background as the one below it,
+ * so the pixels come out about square. The copy is drawn over itself each time it is shown.
+ */
+pub struct TerminalPreview
+{
+    columns: usize,
+    lines: usize // Lines drawn the last time, to move back up over
+}
+
+impl TerminalPreview
+{
+    pub fn new(columns: usize) -> TerminalPreview
+    {
+        TerminalPreview
+        {
+            columns,
+            lines: 0
+        }
+    }
+
+    /**
+     * Draws the image, scaled down to fit the columns, on the standard error so it stays out of the image output
+     */
+    pub fn show(&mut self, image: &Image)
+    {
+        let width = self.columns.clamp(1, image.width());
+        let height = ((image.height() * width) as f32 / image.width() as f32).round().max(1.0) as usize;
+        let pixels = scale_down(image, width, height);
+        let pixel = |x: usize, y: usize| if y < height { to_rgb8(&pixels[x + width * y]) } else { [0, 0, 0] };
+
+        let mut text = String::new();
+        if self.lines > 0
+        {
+            text += &format!("\x1b[{}A", self.lines);
+        }
+        for y in (0..height).step_by(2)
+        {
+            for x in 0..width
+            {
+                let [r, g, b] = pixel(x, y);
+                let [br, bg, bb] = pixel(x, y + 1);
+                text += &format!("\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m\u{2580}", r, g, b, br, bg, bb);
+            }
+            text += "\x1b[0m\n";
+        }
+        self.lines = height.div_ceil(2);
+
+        let _ = io::stderr().lock().write_all(text.as_bytes());
+    }
+}
+
+/**
+ * Averages the pixels of the image into an image of the given size
+ */
+fn scale_down(image: &Image, width: usize, height: usize) -> Vec<Color>
+{
+    let span = |i: usize, size: usize, full: usize| (i * full / size)..((i + 1) * full / size).max(i * full / size + 1);
+
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height
+    {
+        for x in 0..width
+        {
+            let mut sum = Color::new(0.0,0.0,0.0);
+            let mut count = 0;
+            for sy in span(y, height, image.height())
+            {
+                for sx in span(x, width, image.width())
+                {
+                    sum = sum + image.pixel(sx, sy);
+                    count += 1;
+                }
+            }
+            pixels.push(sum.const_div(count as f32));
+        }
+    }
+    pixels
+}
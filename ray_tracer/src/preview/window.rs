@@ -0,0 +1,151 @@
+use std::{rc::Rc, time::{Duration, Instant}};
+
+use minifb::{Key, KeyRepeat, MouseButton, MouseMode, Window, WindowOptions};
+
+use crate::{camera::{camera::Camera, orbit::Orbit}, film::{frame::Frame, region::Region}, objects::{hittable::Hittable, hit_record::HitRecord}, options::Options, scene::Scene, utility::rtweekend::INFINITY, vectors::{color::to_rgb8, vec3::Point3}, IMAGE_HEIGHT, IMAGE_WIDTH};
+
+// Rows rendered between looks at the window
+const BAND_HEIGHT: usize = 8;
+// Time between updates of the window
+const UPDATE_INTERVAL: Duration = Duration::from_millis(50);
+// Degrees the camera moves for each pixel the mouse is dragged, and for each key press
+const DRAG_SPEED: f32 = 0.3;
+const KEY_STEP: f32 = 5.0;
+// Change of the distance to the pivot for each step of the scroll wheel or key press
+const ZOOM_STEP: f32 = 1.1;
+// Distance of the pivot in front of the camera when the middle of the view is empty
+const PIVOT_DISTANCE: f32 = 10.0;
+
+/**
+ * Shows the image in a window while it renders, adding one sample to each pixel at a time, until the window
+ * is closed. Dragging with the mouse or the arrow keys move the camera around the point in the middle of
+ * the view, the scroll wheel and + and - move it closer or further away, and R moves it back. Rendering
+ * starts over whenever the camera moves. Returns the image rendered since the camera last moved, None if
+ * no window could be opened.
+ */
+pub fn run(scene: &Scene, camera: Rc<dyn Camera>, options: &Options) -> Option<Frame>
+{
+    let (width, height) = (IMAGE_WIDTH as usize, IMAGE_HEIGHT as usize);
+    let mut window = match Window::new("ray_tracer", width, height, WindowOptions::default()) {
+        Ok(window) => window,
+        Err(e) => {
+            eprintln!("Could not open the preview window: {}", e);
+            return None;
+        }
+    };
+
+    let mut orbit = Orbit::new(camera.clone(), pivot(scene, camera.as_ref()));
+    let mut frame = Frame::new(width, height, options.filter);
+    let mut buffer = vec![0u32; width * height];
+    let mut passes = 0;
+    let mut row = 0;
+    let mut last_mouse = None;
+    let mut last_update = Instant::now();
+
+    while window.is_open() && !window.is_key_down(Key::Escape)
+    {
+        let band = Region { x: 0, y: row, width, height: BAND_HEIGHT.min(height - row) };
+        crate::render_tile(&orbit, scene, 0.0, options, &mut frame, &band, 1);
+        row += band.height;
+        if row == height
+        {
+            row = 0;
+            passes += 1;
+        }
+        if last_update.elapsed() < UPDATE_INTERVAL
+        {
+            continue;
+        }
+        last_update = Instant::now();
+
+        for (pixel, color) in buffer.iter_mut().zip(frame.color().pixels())
+        {
+            let [r, g, b] = to_rgb8(color);
+            *pixel = (r as u32) << 16 | (g as u32) << 8 | b as u32;
+        }
+        window.set_title(&format!("ray_tracer - {} samples per pixel", passes));
+        if window.update_with_buffer(&buffer, width, height).is_err()
+        {
+            break;
+        }
+
+        if steer(&window, &mut orbit, &mut last_mouse)
+        {
+            frame = Frame::new(width, height, options.filter);
+            passes = 0;
+            row = 0;
+        }
+    }
+
+    Some(frame)
+}
+
+/**
+ * Moves the camera by the mouse and keys, returns true if it moved
+ */
+fn steer(window: &Window, orbit: &mut Orbit, last_mouse: &mut Option<(f32, f32)>) -> bool
+{
+    let mut moved = false;
+
+    // Dragging turns the scene along with the mouse, so the camera goes the other way
+    let mouse = window.get_mouse_pos(MouseMode::Pass);
+    if window.get_mouse_down(MouseButton::Left)
+    {
+        if let (Some((x0, y0)), Some((x, y))) = (*last_mouse, mouse)
+        {
+            if x != x0 || y != y0
+            {
+                orbit.turn(-(x - x0) * DRAG_SPEED, (y - y0) * DRAG_SPEED);
+                moved = true;
+            }
+        }
+        *last_mouse = mouse;
+    } else {
+        *last_mouse = None;
+    }
+
+    if let Some((_, scroll)) = window.get_scroll_wheel()
+    {
+        if scroll != 0.0
+        {
+            orbit.zoom(ZOOM_STEP.powf(-scroll.signum()));
+            moved = true;
+        }
+    }
+
+    for key in window.get_keys_pressed(KeyRepeat::Yes)
+    {
+        match key
+        {
+            Key::Left | Key::A => orbit.turn(-KEY_STEP, 0.0),
+            Key::Right | Key::D => orbit.turn(KEY_STEP, 0.0),
+            Key::Up | Key::W => orbit.turn(0.0, KEY_STEP),
+            Key::Down | Key::S => orbit.turn(0.0, -KEY_STEP),
+            Key::Equal | Key::NumPadPlus => orbit.zoom(1.0 / ZOOM_STEP),
+            Key::Minus | Key::NumPadMinus => orbit.zoom(ZOOM_STEP),
+            Key::R => orbit.reset(),
+            _ => continue
+        }
+        moved = true;
+    }
+
+    moved
+}
+
+/**
+ * Returns the point in the middle of the view, which the camera moves around. Where nothing is there,
+ * a point straight ahead is used.
+ */
+fn pivot(scene: &Scene, camera: &dyn Camera) -> Point3
+{
+    let ray = match camera.get_ray(0.5, 0.5) {
+        Some(ray) => ray,
+        None => return Point3::new(0.0,0.0,0.0)
+    };
+    let mut rec = HitRecord::default();
+    if scene.world.hit(&ray, 0.001, INFINITY, &mut rec) {
+        rec.p
+    } else {
+        ray.at(PIVOT_DISTANCE / ray.direction().length())
+    }
+}
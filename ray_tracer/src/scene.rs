@@ -0,0 +1,74 @@
+use std::rc::Rc;
+
+use crate::{camera::{camera::Camera, camera_animation::CameraAnimation}, environment::environment::Environment, objects::{hittable::Hittable, hittable_list::HittableList, material::material::MaterialIds}, vectors::vec3::Color};
+
+/**
+ * Everything needed to render an image: the objects, the subset of them that emit light, the background or environment
+ * and optionally the camera to view them with, which may be animated
+ */
+pub struct Scene
+{
+    pub world: HittableList,
+    pub lights: HittableList, // Emissive objects, also added to world, that are sampled directly
+    pub background: Option<Color>, // Constant background, or the sky gradient if None
+    pub environment: Option<Rc<dyn Environment>>, // Light from around the scene, replaces the background when set
+    pub camera: Option<Rc<dyn Camera>>, // Camera chosen by the scene, or the default view if None
+    pub camera_animation: Option<Rc<CameraAnimation>> // Moving camera, used instead of camera when set
+}
+
+impl Scene
+{
+    /**
+     * Creates the scene and numbers the materials of its objects, in the order they were added to the world
+     */
+    pub fn new(world: HittableList, lights: HittableList, background: Option<Color>) -> Scene
+    {
+        world.number_materials(&mut MaterialIds::default());
+
+        Scene
+        {
+            world,
+            lights,
+            background,
+            environment: None,
+            camera: None,
+            camera_animation: None
+        }
+    }
+
+    /**
+     * Returns the scene lit by the given environment
+     */
+    pub fn with_environment(self, environment: Rc<dyn Environment>) -> Scene
+    {
+        Scene
+        {
+            environment: Some(environment),
+            ..self
+        }
+    }
+
+    /**
+     * Returns the scene viewed through the given camera
+     */
+    pub fn with_camera(self, camera: Rc<dyn Camera>) -> Scene
+    {
+        Scene
+        {
+            camera: Some(camera),
+            ..self
+        }
+    }
+
+    /**
+     * Returns the scene viewed through a keyframed camera
+     */
+    pub fn with_camera_animation(self, animation: CameraAnimation) -> Scene
+    {
+        Scene
+        {
+            camera_animation: Some(Rc::new(animation)),
+            ..self
+        }
+    }
+}
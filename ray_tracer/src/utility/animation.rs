@@ -0,0 +1,100 @@
+use crate::vectors::vec3::Vec3;
+
+/**
+ * Values that can be blended for animation
+ */
+pub trait Interpolate: Copy
+{
+    /**
+     * Catmull-Rom spline through p1 and p2 at t in [0,1], shaped by their neighbours p0 and p3
+     */
+    fn catmull_rom(p0: Self, p1: Self, p2: Self, p3: Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32
+{
+    fn catmull_rom(p0: f32, p1: f32, p2: f32, p3: f32, t: f32) -> f32
+    {
+        let t2 = t*t;
+        let t3 = t2*t;
+        0.5 * ((2.0*p1)
+            + (p2 - p0)*t
+            + (2.0*p0 - 5.0*p1 + 4.0*p2 - p3)*t2
+            + (3.0*p1 - p0 - 3.0*p2 + p3)*t3)
+    }
+}
+
+impl Interpolate for Vec3
+{
+    fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3
+    {
+        Vec3::new(
+            f32::catmull_rom(p0.x(), p1.x(), p2.x(), p3.x(), t),
+            f32::catmull_rom(p0.y(), p1.y(), p2.y(), p3.y(), t),
+            f32::catmull_rom(p0.z(), p1.z(), p2.z(), p3.z(), t))
+    }
+}
+
+/**
+ * A value keyframed over time. Between keyframes the value follows a smooth curve through them, before the
+ * first and after the last keyframe it holds still.
+ */
+#[derive(Clone)]
+pub struct Track<T: Interpolate>
+{
+    keys: Vec<(f32, T)> // (time, value), sorted by time
+}
+
+impl<T: Interpolate> Track<T>
+{
+    /**
+     * Returns a track that always has the given value
+     */
+    pub fn constant(value: T) -> Track<T>
+    {
+        Track
+        {
+            keys: vec![(0.0, value)]
+        }
+    }
+
+    /**
+     * Returns a track through the given (time, value) keyframes, which must not be empty
+     */
+    pub fn new(mut keys: Vec<(f32, T)>) -> Track<T>
+    {
+        assert!(!keys.is_empty(), "a track needs at least one keyframe");
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Track
+        {
+            keys
+        }
+    }
+
+    /**
+     * Returns the value at the given time
+     */
+    pub fn at(&self, time: f32) -> T
+    {
+        let last = self.keys.len() - 1;
+        if time <= self.keys[0].0
+        {
+            return self.keys[0].1;
+        }
+        if time >= self.keys[last].0
+        {
+            return self.keys[last].1;
+        }
+
+        // Keyframes i and i+1 are on either side of the time
+        let i = self.keys.iter().rposition(|key| key.0 <= time).unwrap_or(0).min(last - 1);
+        let (t1, p1) = self.keys[i];
+        let (t2, p2) = self.keys[i + 1];
+        let p0 = if i > 0 { self.keys[i - 1].1 } else { p1 };
+        let p3 = if i + 2 <= last { self.keys[i + 2].1 } else { p2 };
+
+        let t = if t2 > t1 { (time - t1) / (t2 - t1) } else { 0.0 };
+        T::catmull_rom(p0, p1, p2, p3, t)
+    }
+}
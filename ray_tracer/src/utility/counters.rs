@@ -0,0 +1,22 @@
+use std::cell::Cell;
+
+thread_local!
+{
+    static INTERSECTION_TESTS: Cell<u32> = const { Cell::new(0) };
+}
+
+/**
+ * Counts one object tested against a ray, to show where rays are expensive
+ */
+pub fn count_intersection_test()
+{
+    INTERSECTION_TESTS.with(|count| count.set(count.get() + 1));
+}
+
+/**
+ * Returns the number of objects tested since the last call, and starts counting again from zero
+ */
+pub fn take_intersection_tests() -> u32
+{
+    INTERSECTION_TESTS.with(|count| count.replace(0))
+}
@@ -0,0 +1,311 @@
+use std::{fs, io, io::Write};
+
+use crate::vectors::{color::write_color, vec3::Color};
+
+/**
+ * An image of linear colors, stored row by row from the top left corner
+ */
+pub struct Image
+{
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>
+}
+
+impl Image
+{
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Image
+    {
+        Image
+        {
+            width,
+            height,
+            pixels
+        }
+    }
+
+    /**
+     * Loads a Netpbm image, in plain (P2, P3) or binary (P5, P6) form. Values are scaled to [0,1] by the
+     * maximum value in the header.
+     */
+    pub fn load(path: &str) -> io::Result<Image>
+    {
+        let bytes = fs::read(path)?;
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path, message));
+
+        // Header tokens, skipping comments
+        let mut pos = 0;
+        let mut next_token = || -> Option<String> {
+            loop
+            {
+                while pos < bytes.len() && bytes[pos].is_ascii_whitespace()
+                {
+                    pos += 1;
+                }
+                if pos < bytes.len() && bytes[pos] == b'#'
+                {
+                    while pos < bytes.len() && bytes[pos] != b'\n'
+                    {
+                        pos += 1;
+                    }
+                    continue;
+                }
+                break;
+            }
+            let start = pos;
+            while pos < bytes.len() && !bytes[pos].is_ascii_whitespace()
+            {
+                pos += 1;
+            }
+            if start == pos { None } else { Some(String::from_utf8_lossy(&bytes[start..pos]).into_owned()) }
+        };
+
+        let magic = next_token().ok_or_else(|| invalid("empty file"))?;
+        let (channels, binary) = match magic.as_str() {
+            "P2" => (1, false),
+            "P3" => (3, false),
+            "P5" => (1, true),
+            "P6" => (3, true),
+            _ => return Err(invalid("not a PGM or PPM image"))
+        };
+        let mut header = || -> io::Result<usize> {
+            next_token()
+                .and_then(|token| token.parse::<usize>().ok())
+                .ok_or_else(|| invalid("bad header"))
+        };
+        let width = header()?;
+        let height = header()?;
+        let max_value = header()?;
+        if width == 0 || height == 0 || max_value == 0
+        {
+            return Err(invalid("bad header"));
+        }
+
+        let count = width * height * channels;
+        let values: Vec<f32> = if binary {
+            // A single whitespace character separates the header from the data
+            let start = pos + 1;
+            let bytes_per_value = if max_value > 255 { 2 } else { 1 };
+            if bytes.len() < start + count * bytes_per_value
+            {
+                return Err(invalid("image data ended early"));
+            }
+            (0..count).map(|i| {
+                let offset = start + i * bytes_per_value;
+                if bytes_per_value == 2 {
+                    u16::from_be_bytes([bytes[offset], bytes[offset + 1]]) as f32
+                } else {
+                    bytes[offset] as f32
+                }
+            }).collect()
+        } else {
+            let mut values = Vec::with_capacity(count);
+            for _ in 0..count
+            {
+                let value = next_token()
+                    .and_then(|token| token.parse::<f32>().ok())
+                    .ok_or_else(|| invalid("image data ended early"))?;
+                values.push(value);
+            }
+            values
+        };
+
+        let scale = 1.0 / max_value as f32;
+        let pixels = values.chunks(channels).map(|v| {
+            if channels == 1 {
+                Color::new(v[0], v[0], v[0]).const_mul(scale)
+            } else {
+                Color::new(v[0], v[1], v[2]).const_mul(scale)
+            }
+        }).collect();
+
+        Ok(Image::new(width, height, pixels))
+    }
+
+    /**
+     * Loads a Radiance RGBE image (.hdr), flat or with run length encoded scanlines
+     */
+    pub fn load_hdr(path: &str) -> io::Result<Image>
+    {
+        let bytes = fs::read(path)?;
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path, message));
+
+        // Header lines end with an empty line, followed by the resolution line
+        let mut pos = 0;
+        let mut next_line = || -> Option<String> {
+            let start = pos;
+            while pos < bytes.len() && bytes[pos] != b'\n'
+            {
+                pos += 1;
+            }
+            if pos >= bytes.len()
+            {
+                return None;
+            }
+            pos += 1;
+            Some(String::from_utf8_lossy(&bytes[start..pos - 1]).trim().to_string())
+        };
+
+        let magic = next_line().ok_or_else(|| invalid("empty file"))?;
+        if !magic.starts_with("#?")
+        {
+            return Err(invalid("not a Radiance HDR image"));
+        }
+        loop
+        {
+            let line = next_line().ok_or_else(|| invalid("header ended early"))?;
+            if line.is_empty()
+            {
+                break;
+            }
+            if line.starts_with("FORMAT=") && line != "FORMAT=32-bit_rle_rgbe"
+            {
+                return Err(invalid("only RGB images are supported"));
+            }
+        }
+
+        // Only the standard orientation, top to bottom and left to right, is supported
+        let resolution = next_line().ok_or_else(|| invalid("missing resolution"))?;
+        let fields: Vec<&str> = resolution.split_whitespace().collect();
+        if fields.len() != 4 || fields[0] != "-Y" || fields[2] != "+X"
+        {
+            return Err(invalid("unsupported resolution line"));
+        }
+        let height = fields[1].parse::<usize>().map_err(|_| invalid("bad resolution"))?;
+        let width = fields[3].parse::<usize>().map_err(|_| invalid("bad resolution"))?;
+        if width == 0 || height == 0
+        {
+            return Err(invalid("bad resolution"));
+        }
+
+        let mut pixels = Vec::with_capacity(width * height);
+        let mut scanline = vec![[0u8; 4]; width];
+        let mut byte = || -> io::Result<u8> {
+            let value = *bytes.get(pos).ok_or_else(|| invalid("image data ended early"))?;
+            pos += 1;
+            Ok(value)
+        };
+        for _ in 0..height
+        {
+            let first = [byte()?, byte()?, byte()?, byte()?];
+            let encoded_width = ((first[2] as usize) << 8) | first[3] as usize;
+            if (8..0x8000).contains(&width) && first[0] == 2 && first[1] == 2 && encoded_width == width
+            {
+                // Each of the four components is run length encoded separately
+                for component in 0..4
+                {
+                    let mut x = 0;
+                    while x < width
+                    {
+                        let count = byte()? as usize;
+                        if count > 128
+                        {
+                            let run = count - 128;
+                            let value = byte()?;
+                            if x + run > width
+                            {
+                                return Err(invalid("bad run length"));
+                            }
+                            for pixel in &mut scanline[x..x + run]
+                            {
+                                pixel[component] = value;
+                            }
+                            x += run;
+                        } else {
+                            if count == 0 || x + count > width
+                            {
+                                return Err(invalid("bad run length"));
+                            }
+                            for pixel in &mut scanline[x..x + count]
+                            {
+                                pixel[component] = byte()?;
+                            }
+                            x += count;
+                        }
+                    }
+                }
+            } else {
+                scanline[0] = first;
+                for pixel in scanline.iter_mut().skip(1)
+                {
+                    *pixel = [byte()?, byte()?, byte()?, byte()?];
+                }
+            }
+
+            pixels.extend(scanline.iter().map(rgbe_to_color));
+        }
+
+        Ok(Image::new(width, height, pixels))
+    }
+
+    pub fn width(&self) -> usize
+    {
+        self.width
+    }
+
+    pub fn height(&self) -> usize
+    {
+        self.height
+    }
+
+    /**
+     * Returns all pixels, row by row from the top left corner
+     */
+    pub fn pixels(&self) -> &[Color]
+    {
+        &self.pixels
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Color
+    {
+        self.pixels[x + self.width * y]
+    }
+
+    /**
+     * Writes the image as a plain PPM, gamma corrected, with an optional comment line in the header
+     */
+    pub fn write_ppm(&self, handle: &mut dyn Write, comment: Option<&str>) -> io::Result<()>
+    {
+        let comment = comment.map_or(String::new(), |comment| format!("# {}\n", comment));
+        let header = format!("P3\n{}{} {}\n255\n", comment, self.width, self.height);
+        handle.write_all(header.as_bytes())?;
+        for pixel in &self.pixels
+        {
+            write_color(handle, pixel, 1)?;
+        }
+        Ok(())
+    }
+
+    /**
+     * Writes the image as a color PFM, which keeps the values as they are. The rows are written from the bottom.
+     */
+    pub fn write_pfm(&self, handle: &mut dyn Write) -> io::Result<()>
+    {
+        let header = format!("PF\n{} {}\n-1.0\n", self.width, self.height);
+        handle.write_all(header.as_bytes())?;
+        for row in self.pixels.chunks(self.width).rev()
+        {
+            for pixel in row
+            {
+                for value in [pixel.x(), pixel.y(), pixel.z()]
+                {
+                    handle.write_all(&value.to_le_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Decodes a pixel with a shared exponent
+ */
+fn rgbe_to_color(rgbe: &[u8; 4]) -> Color
+{
+    if rgbe[3] == 0
+    {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+    let scale = 2.0_f32.powi(rgbe[3] as i32 - (128 + 8));
+    Color::new(rgbe[0] as f32, rgbe[1] as f32, rgbe[2] as f32).const_mul(scale)
+}
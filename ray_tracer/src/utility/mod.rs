@@ -1 +1,6 @@
-pub mod rtweekend;
\ No newline at end of file
+pub mod rtweekend;
+pub mod perlin;
+pub mod image;
+pub mod animation;
+pub mod counters;
+pub mod polynomial;
\ No newline at end of file
@@ -0,0 +1,112 @@
+use crate::vectors::vec3::{Point3, Vec3, dot, random_vec_custom};
+
+use super::rtweekend::random_number;
+
+const POINT_COUNT: usize = 256;
+
+/**
+ * Perlin noise generator with random gradient vectors
+ */
+pub struct Perlin
+{
+    ranvec: Vec<Vec3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>
+}
+
+impl Default for Perlin
+{
+    fn default() -> Self {
+        Perlin::new()
+    }
+}
+
+impl Perlin
+{
+    pub fn new() -> Perlin
+    {
+        let ranvec = (0..POINT_COUNT).map(|_| random_vec_custom(-1.0, 1.0).unit_vector()).collect();
+
+        Perlin {
+            ranvec,
+            perm_x: generate_perm(),
+            perm_y: generate_perm(),
+            perm_z: generate_perm()
+        }
+    }
+
+    /**
+     * Returns smooth noise in [-1,1] for the given point
+     */
+    pub fn noise(&self, p: &Point3) -> f32
+    {
+        let u = p.x() - p.x().floor();
+        let v = p.y() - p.y().floor();
+        let w = p.z() - p.z().floor();
+
+        let i = p.x().floor() as i32;
+        let j = p.y().floor() as i32;
+        let k = p.z().floor() as i32;
+
+        let mut c = [[[Vec3::default(); 2]; 2]; 2];
+        for (di, plane) in c.iter_mut().enumerate()
+        {
+            for (dj, row) in plane.iter_mut().enumerate()
+            {
+                for (dk, corner) in row.iter_mut().enumerate()
+                {
+                    let index = self.perm_x[((i + di as i32) & 255) as usize]
+                        ^ self.perm_y[((j + dj as i32) & 255) as usize]
+                        ^ self.perm_z[((k + dk as i32) & 255) as usize];
+                    *corner = self.ranvec[index];
+                }
+            }
+        }
+
+        perlin_interp(&c, u, v, w)
+    }
+}
+
+/**
+ * Returns a random permutation of [0, POINT_COUNT)
+ */
+fn generate_perm() -> Vec<usize>
+{
+    let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+    for i in (1..POINT_COUNT).rev()
+    {
+        let target = ((random_number() * (i + 1) as f32) as usize).min(i);
+        p.swap(i, target);
+    }
+    p
+}
+
+/**
+ * Trilinear interpolation of the corner gradients with Hermite smoothing
+ */
+fn perlin_interp(c: &[[[Vec3; 2]; 2]; 2], u: f32, v: f32, w: f32) -> f32
+{
+    let uu = u*u*(3.0-2.0*u);
+    let vv = v*v*(3.0-2.0*v);
+    let ww = w*w*(3.0-2.0*w);
+    let mut accum = 0.0;
+
+    for (i, plane) in c.iter().enumerate()
+    {
+        for (j, row) in plane.iter().enumerate()
+        {
+            for (k, corner) in row.iter().enumerate()
+            {
+                let (fi, fj, fk) = (i as f32, j as f32, k as f32);
+                let weight_v = Vec3::new(u-fi, v-fj, w-fk);
+                accum += (fi*uu + (1.0-fi)*(1.0-uu))
+                    * (fj*vv + (1.0-fj)*(1.0-vv))
+                    * (fk*ww + (1.0-fk)*(1.0-ww))
+                    * dot(corner, &weight_v);
+            }
+        }
+    }
+
+    accum
+}
@@ -0,0 +1,151 @@
+use std::f64::consts::PI;
+
+// Coefficients closer to zero than this are taken as zero
+const EPSILON: f64 = 1e-9;
+
+/*
+ * Closed form real roots of polynomials up to degree four, after Jochen Schwarze's "Cubic and Quartic Roots"
+ * in Graphics Gems. Coefficients are given from the constant term up, the leading one must not be zero.
+ * The roots come out in no particular order, a double root may be given once.
+ */
+
+fn is_zero(x: f64) -> bool
+{
+    x.abs() < EPSILON
+}
+
+/**
+ * Real roots of k2 x^2 + k1 x + k0, from the coefficients [k0, k1, k2]
+ */
+pub fn solve_quadratic(coefficients: [f64; 3]) -> Vec<f64>
+{
+    // Normal form x^2 + 2px + q
+    let p = coefficients[1] / (2.0 * coefficients[2]);
+    let q = coefficients[0] / coefficients[2];
+    let d = p*p - q;
+
+    if is_zero(d) {
+        vec![-p]
+    } else if d < 0.0 {
+        Vec::new()
+    } else {
+        let sqrt_d = d.sqrt();
+        vec![sqrt_d - p, -sqrt_d - p]
+    }
+}
+
+/**
+ * Real roots of k3 x^3 + k2 x^2 + k1 x + k0, from the coefficients [k0, k1, k2, k3]
+ */
+pub fn solve_cubic(coefficients: [f64; 4]) -> Vec<f64>
+{
+    // Normal form x^3 + ax^2 + bx + c, then x = y - a/3 removes the square, giving y^3 + 3py + 2q
+    let [c, b, a, _] = coefficients.map(|k| k / coefficients[3]);
+    let sq_a = a*a;
+    let p = (-sq_a / 3.0 + b) / 3.0;
+    let q = (2.0 / 27.0 * a * sq_a - a * b / 3.0 + c) / 2.0;
+    let cb_p = p*p*p;
+    let d = q*q + cb_p;
+
+    let roots = if is_zero(d) {
+        if is_zero(q) {
+            vec![0.0]
+        } else {
+            let u = (-q).cbrt();
+            vec![2.0 * u, -u]
+        }
+    } else if d < 0.0 {
+        // Three real roots
+        let phi = (-q / (-cb_p).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        vec![t * phi.cos(), -t * (phi + PI / 3.0).cos(), -t * (phi - PI / 3.0).cos()]
+    } else {
+        let sqrt_d = d.sqrt();
+        vec![(sqrt_d - q).cbrt() - (sqrt_d + q).cbrt()]
+    };
+
+    roots.into_iter().map(|y| y - a / 3.0).collect()
+}
+
+/**
+ * Real roots of k4 x^4 + k3 x^3 + k2 x^2 + k1 x + k0, from the coefficients [k0, k1, k2, k3, k4]
+ */
+pub fn solve_quartic(coefficients: [f64; 5]) -> Vec<f64>
+{
+    // Normal form x^4 + ax^3 + bx^2 + cx + d, then x = y - a/4 removes the cube, giving y^4 + py^2 + qy + r
+    let [d, c, b, a, _] = coefficients.map(|k| k / coefficients[4]);
+    let sq_a = a*a;
+    let p = -3.0 / 8.0 * sq_a + b;
+    let q = sq_a * a / 8.0 - a * b / 2.0 + c;
+    let r = -3.0 / 256.0 * sq_a * sq_a + sq_a * b / 16.0 - a * c / 4.0 + d;
+
+    let mut roots = if is_zero(r) {
+        // No constant term, y(y^3 + py + q) = 0
+        let mut roots = solve_cubic([q, p, 0.0, 1.0]);
+        roots.push(0.0);
+        roots
+    } else {
+        // Split into two quadratics with a root of the resolvent cubic
+        let z = solve_cubic([r * p / 2.0 - q * q / 8.0, -r, -p / 2.0, 1.0])[0];
+        let u = z*z - r;
+        let v = 2.0*z - p;
+        let u = if is_zero(u) { 0.0 } else if u > 0.0 { u.sqrt() } else { return Vec::new() };
+        let v = if is_zero(v) { 0.0 } else if v > 0.0 { v.sqrt() } else { return Vec::new() };
+
+        let mut roots = solve_quadratic([z - u, if q < 0.0 { -v } else { v }, 1.0]);
+        roots.extend(solve_quadratic([z + u, if q < 0.0 { v } else { -v }, 1.0]));
+        roots
+    };
+
+    for root in &mut roots
+    {
+        *root -= a / 4.0;
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /**
+     * Checks that every root is one of the expected ones and every expected one is found, a double root once or twice
+     */
+    fn assert_roots(roots: Vec<f64>, expected: &[f64])
+    {
+        let near = |a: f64, b: f64| (a - b).abs() < 1e-6;
+        assert!(roots.iter().all(|root| expected.iter().any(|e| near(*root, *e))), "{:?} instead of {:?}", roots, expected);
+        assert!(expected.iter().all(|e| roots.iter().any(|root| near(*root, *e))), "{:?} instead of {:?}", roots, expected);
+    }
+
+    #[test]
+    fn quadratic()
+    {
+        assert_roots(solve_quadratic([3.0, -4.0, 1.0]), &[1.0, 3.0]);
+        assert_roots(solve_quadratic([-2.0, 0.0, 2.0]), &[-1.0, 1.0]);
+        assert_roots(solve_quadratic([4.0, -4.0, 1.0]), &[2.0]);
+        assert_roots(solve_quadratic([1.0, 0.0, 1.0]), &[]);
+        assert_roots(solve_quadratic([0.0, -5.0, 1.0]), &[0.0, 5.0]);
+    }
+
+    #[test]
+    fn cubic()
+    {
+        assert_roots(solve_cubic([-6.0, 11.0, -6.0, 1.0]), &[1.0, 2.0, 3.0]);
+        assert_roots(solve_cubic([-2.0, 1.0, -2.0, 1.0]), &[2.0]);
+        assert_roots(solve_cubic([2.0, -3.0, 0.0, 1.0]), &[1.0, -2.0]);
+        assert_roots(solve_cubic([8.0, 12.0, 6.0, 1.0]), &[-2.0]);
+        assert_roots(solve_cubic([0.0, -2.0, 0.0, 2.0]), &[-1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn quartic()
+    {
+        assert_roots(solve_quartic([24.0, -50.0, 35.0, -10.0, 1.0]), &[1.0, 2.0, 3.0, 4.0]);
+        assert_roots(solve_quartic([2.0, -3.0, 3.0, -3.0, 1.0]), &[1.0, 2.0]);
+        assert_roots(solve_quartic([1.0, 0.0, -2.0, 0.0, 1.0]), &[-1.0, 1.0]);
+        assert_roots(solve_quartic([4.0, 0.0, 5.0, 0.0, 1.0]), &[]);
+        assert_roots(solve_quartic([0.0, -6.0, 11.0, -6.0, 1.0]), &[0.0, 1.0, 2.0, 3.0]);
+    }
+}
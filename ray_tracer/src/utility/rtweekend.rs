@@ -1,29 +1,71 @@
-use rand::{thread_rng, Rng};
-
-// Constanst
-pub const INFINITY: f32 = 1.0_f32 / 0.0_f32;
-pub const PI: f64 = 3.14159265358979323846264338327950288f64;
-
-// Utility functions
-pub fn degrees_to_radians(degrees: f32) -> f64
-{
-    (degrees as f64 * PI) / 180.0
-}
-
-/**
- * Returns a random number in [0,1)
- */
-pub fn random_number() -> f32 
-{
-    let mut rng = rand::thread_rng();
-    rng.gen_range(0.0,1.0)
-}
-
-/**
- * Returns a random number in [MIN, MAX)
- */
-pub fn random_number_custom(min: f32, max: f32) -> f32 
-{
-    let mut rng = rand::thread_rng();
-    rng.gen_range(min, max)
-}
+use std::cell::RefCell;
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+thread_local!
+{
+    static RNG: RefCell<XorShiftRng> = RefCell::new(XorShiftRng::from_seed(seed_words(0)));
+}
+
+// Constanst
+pub const INFINITY: f32 = 1.0_f32 / 0.0_f32;
+pub const PI: f64 = 3.14159265358979323846264338327950288f64;
+
+// Utility functions
+pub fn degrees_to_radians(degrees: f32) -> f64
+{
+    (degrees as f64 * PI) / 180.0
+}
+
+/**
+ * Returns a random number in [0,1)
+ */
+pub fn random_number() -> f32 
+{
+    RNG.with(|rng| rng.borrow_mut().gen_range(0.0,1.0))
+}
+
+/**
+ * Returns a random number in [MIN, MAX)
+ */
+pub fn random_number_custom(min: f32, max: f32) -> f32 
+{
+    RNG.with(|rng| rng.borrow_mut().gen_range(min, max))
+}
+
+/**
+ * Restarts the random numbers of this thread from the seed, the same seed always gives the same numbers
+ */
+pub fn seed_random(seed: u64)
+{
+    RNG.with(|rng| *rng.borrow_mut() = XorShiftRng::from_seed(seed_words(seed)));
+}
+
+/**
+ * Combines a seed with a value into a new seed, for giving each part of the work its own random numbers
+ */
+pub fn mix_seed(seed: u64, value: u64) -> u64
+{
+    splitmix(seed ^ splitmix(value))
+}
+
+/**
+ * The SplitMix64 step, which scatters nearby inputs far apart
+ */
+fn splitmix(x: u64) -> u64
+{
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/**
+ * Spreads a seed over the state of the generator, which must not be all zero
+ */
+fn seed_words(seed: u64) -> [u32; 4]
+{
+    let a = splitmix(seed);
+    let b = splitmix(a);
+    [a as u32, (a >> 32) as u32, b as u32, ((b >> 32) as u32) | 1]
+}
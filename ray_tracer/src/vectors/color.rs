@@ -1,104 +1,306 @@
-use crate::{Color, objects::{hittable::Hittable, hit_record::HitRecord, hittable_list::HittableList}};
-
-use std::{io::{Write, StdoutLock}, f32::INFINITY};
-
-use super::{ray::Ray, vec3::{Point3, dot, Vec3, random_unit_vector}};
-
-// Constants
-const SPHERE_INTERSECT: f32 = 0.001;
-
-/**
- * A utility function to write a single pixel's color out to the standard output stream
- */
-pub fn write_color(handle: &mut StdoutLock, color: &Color, samples_per_pixel: i32)
-{  
-    // Fetch rgb data
-    let mut r = color.x();
-    let mut g = color.y();
-    let mut b = color.z();
-
-    // Divide the color by the number of samples and gamma-correct for gamma=2.0.
-    let scale: f32 = 1.0 / samples_per_pixel as f32;
-    r = (r*scale).sqrt();
-    g = (g*scale).sqrt();
-    b = (b*scale).sqrt();
-
-    // Write the translated [0,255] value of each color component.
-    r = 256.0 * clamp(r, 0.0, 0.999);
-    g = 256.0 * clamp(g, 0.0, 0.999);
-    b = 256.0 * clamp(b, 0.0, 0.999);
-
-    let output = format!("{} {} {}\n",r, g, b);
-    handle.write_all(output.as_bytes());
-}
-
-/**
- *  A function that check if a ray will hit any object, if no object is hit will return no light(color(0,0,0))
- */
-pub fn ray_color(r: &Ray, world: &HittableList, depth: i32) -> Color
-{
-    let mut rec = HitRecord::default();
-
-    // If we've exceeded the ray bounce limit, no more light is gathered.
-    if depth <= 0
-    {
-        return Color::new(0.0,0.0,0.0);
-    }
-
-    // Check if ray hit anything
-    if world.hit(r, SPHERE_INTERSECT, INFINITY, &mut rec)
-    {
-        let mut scattered = Ray::default();
-        let mut attenuation = Color::default();
-        if rec.mat_ptr.scatter(r, &rec, &mut attenuation, &mut scattered)
-        {
-            return attenuation * ray_color(&scattered, world, depth-1)
-        }
-        return Color::new(0.0,0.0,0.0)
-        // Calculate target by creating random ray's around unit sphere from 
-        // impact point.
-        //let target = rec.p + rec.normal + random_unit_vector();
-        //return ray_color(&Ray::new(rec.p, target - rec.p), world, depth-1).const_mul(0.5);
-        //return rec.normal + Color::new(1.0,1.0,1.0).const_mul(0.5);
-    }
-
-    // Not hit, will be background
-    let unit_direction = r.direction().unit_vector();
-    let t = 0.5*(unit_direction.y() + 1.0);
-    Color::new(1.0,1.0,1.0).const_mul(1.0-t) + Color::new(0.5,0.7,1.0).const_mul(t)
-}
-
-/**
- * Clamps given value to the given range
- */
-pub fn clamp(x: f32, min: f32, max: f32) -> f32
-{
-    if x < min {
-        return min;
-    } else if x > max {
-        return max;
-    }
-
-    return x;
-}
-
-/*
- * Simple function, that hard codes a sphere in the image
- */
-/*pub fn hit_sphere(center: Point3, radius: f32, r: &Ray) -> f32
-{
-    let oc = r.origin() - center;
-    let a = r.direction().length_squared();
-    let half_b = dot(&oc, &r.direction());
-    let c = oc.length_squared() - (radius*radius);
-    let discriminant = half_b*half_b - a*c;
-
-    // Check if the ray hit the ball
-    if discriminant < 0.0
-    {
-        return -1.0
-    } else 
-    {
-        return (-half_b - discriminant.sqrt()) / a;
-    }
+use crate::{Color, film::features::Features, objects::{hittable::Hittable, hit_record::HitRecord, material::scatter_record::ScatterRecord}, pdf::{pdf::Pdf, light_pdf::LightPdf, mixture_pdf::MixturePdf}, scene::Scene, utility::rtweekend::{INFINITY, random_number}};
+
+use std::{io, io::Write};
+
+use super::{ray::Ray, spectrum::sample_color, vec3::Point3};
+
+// Constants
+const SPHERE_INTERSECT: f32 = 0.001;
+const RR_MIN_DEPTH: i32 = 3; // Bounces before Russian roulette may end a path
+
+// Wavelengths in nm that represent the red, green and blue channels when a ray is split by dispersion
+pub const RGB_WAVELENGTHS: [f32; 3] = [630.0, 532.0, 465.0];
+
+/**
+ * A utility function to write a single pixel's color out to the given stream
+ */
+pub fn write_color(handle: &mut dyn Write, color: &Color, samples_per_pixel: i32) -> io::Result<()>
+{  
+    // Fetch rgb data
+    let mut r = color.x();
+    let mut g = color.y();
+    let mut b = color.z();
+
+    // Divide the color by the number of samples and gamma-correct for gamma=2.0.
+    // Spectral samples can fall outside the RGB gamut, so negative values are cut off first.
+    let scale: f32 = 1.0 / samples_per_pixel as f32;
+    r = (r*scale).max(0.0).sqrt();
+    g = (g*scale).max(0.0).sqrt();
+    b = (b*scale).max(0.0).sqrt();
+
+    // Write the translated [0,255] value of each color component.
+    r = 256.0 * clamp(r, 0.0, 0.999);
+    g = 256.0 * clamp(g, 0.0, 0.999);
+    b = 256.0 * clamp(b, 0.0, 0.999);
+
+    let output = format!("{} {} {}\n", r as u8, g as u8, b as u8);
+    handle.write_all(output.as_bytes())
+}
+
+/**
+ * Gamma corrects a color into 8-bit red, green and blue, for showing it on screen
+ */
+pub fn to_rgb8(color: &Color) -> [u8; 3]
+{
+    let channel = |value: f32| (256.0 * clamp(value.max(0.0).sqrt(), 0.0, 0.999)) as u8;
+    [channel(color.x()), channel(color.y()), channel(color.z())]
+}
+
+/**
+ *  A function that check if a ray will hit any object, if no object is hit will return no light(color(0,0,0))
+ *  The path is followed iteratively, keeping the product of all attenuations so far as throughput. After
+ *  RR_MIN_DEPTH bounces paths are ended at random by Russian roulette, based on how much light they can still carry.
+ *  The albedo and normal of the first surface that is not a mirror or glass are stored in features, along with
+ *  what the ray hit first and how many times the path scattered.
+ */
+pub fn ray_color(r: &Ray, scene: &Scene, max_depth: i32, features: &mut Features) -> Color
+{
+    let mut guide = Some(&mut *features); // Taken once the albedo and normal are stored
+    let mut bounces = 0;
+    let mut radiance = Color::new(0.0,0.0,0.0);
+    let mut throughput = Color::new(1.0,1.0,1.0);
+    let mut ray = *r;
+    // If the ray was sampled from a pdf that lights were also sampled against, holds the scatter point
+    // and that pdf's value so emission found by the ray can be weighted against light sampling.
+    let mut mis_origin: Option<(Point3, f32)> = None;
+
+    // If we've exceeded the ray bounce limit, no more light is gathered.
+    for depth in 0..max_depth
+    {
+        let mut rec = HitRecord::default();
+
+        // Check if ray hit anything
+        if !scene.world.hit(&ray, SPHERE_INTERSECT, INFINITY, &mut rec)
+        {
+            // Not hit, will be background. An environment is also sampled as a light.
+            let mut light = background(&ray, scene);
+            if let Some(features) = guide.take()
+            {
+                features.albedo = saturate(&light);
+            }
+            if let (Some((origin, scatter_pdf)), Some(_)) = (mis_origin, &scene.environment)
+            {
+                let light_pdf = LightPdf::new(scene, origin).value(&ray.direction());
+                light = light.const_mul(power_heuristic(scatter_pdf, light_pdf));
+            }
+            radiance = radiance + throughput * light;
+            break;
+        }
+        throughput = throughput * rec.weight;
+
+        if depth == 0
+        {
+            if let Some(features) = guide.as_mut()
+            {
+                features.depth = rec.t * ray.direction().length();
+                features.material = rec.material_id;
+                features.object_id = Some(rec.object_id);
+            }
+        }
+
+        let mut emitted = rec.mat_ptr.emitted(&ray, &rec);
+        if let Some((origin, scatter_pdf)) = mis_origin
+        {
+            if !emitted.near_zero()
+            {
+                let light_pdf = LightPdf::new(scene, origin).value(&ray.direction());
+                emitted = emitted.const_mul(power_heuristic(scatter_pdf, light_pdf));
+            }
+        }
+        radiance = radiance + throughput * emitted;
+
+        let mut srec = ScatterRecord::default();
+        if !rec.mat_ptr.scatter(&ray, &rec, &mut srec)
+        {
+            if let Some(features) = guide.take()
+            {
+                features.albedo = saturate(&emitted);
+                features.normal = rec.normal;
+            }
+            break;
+        }
+        bounces += 1;
+
+        match srec.pdf.clone()
+        {
+            None => {
+                // Specular, lights can only be found by following the ray. If the ray continues
+                // unchanged it still carries the pdf it was sampled with.
+                if !rec.mat_ptr.passes_through()
+                {
+                    mis_origin = None;
+                }
+                throughput = throughput * srec.attenuation;
+                ray = carry_over(&ray, srec.specular_ray);
+            }
+            Some(material_pdf) => {
+                if let Some(features) = guide.take()
+                {
+                    features.albedo = rec.mat_ptr.albedo(&ray, &rec, &srec);
+                    features.normal = rec.normal;
+                }
+
+                // Continue along a direction from the material, mixed with directions towards the lights
+                let light_pdf = LightPdf::new(scene, rec.p);
+                let mixture_pdf = MixturePdf::new(&light_pdf, material_pdf.as_ref());
+                let scatter_pdf: &dyn Pdf = if light_pdf.is_empty() { material_pdf.as_ref() } else { &mixture_pdf };
+
+                let scattered = Ray::new(rec.p, scatter_pdf.generate());
+                let pdf_value = scatter_pdf.value(&scattered.direction());
+                if pdf_value <= 0.0
+                {
+                    break;
+                }
+                let bsdf = rec.mat_ptr.bsdf(&ray, &rec, &srec, &scattered);
+
+                radiance = radiance + throughput * sample_lights(&ray, &rec, &srec, scatter_pdf, scene);
+                throughput = (throughput * bsdf).const_div(pdf_value);
+                mis_origin = Some((rec.p, pdf_value));
+                ray = carry_over(&ray, scattered);
+            }
+        }
+
+        // Russian roulette, surviving paths are boosted to keep the estimate unbiased
+        if depth + 1 >= RR_MIN_DEPTH
+        {
+            let survive = throughput.max_component().min(0.95);
+            if random_number() >= survive
+            {
+                break;
+            }
+            throughput = throughput.const_div(survive);
+        }
+    }
+
+    features.bounces = bounces;
+    radiance
+}
+
+/**
+ * Once a ray has been split up by dispersion, the rays scattered from it stay at the same wavelength.
+ * Spectral rays pass on the wavelengths they carry, and all rays pass on their time.
+ */
+fn carry_over(r_in: &Ray, scattered: Ray) -> Ray
+{
+    let scattered = scattered.with_spectrum(r_in.spectrum()).with_time(r_in.time());
+    if scattered.wavelength().is_some()
+    {
+        return scattered;
+    }
+    scattered.with_wavelength(r_in.wavelength())
+}
+
+/**
+ * Returns the light from the background in the direction of the ray
+ */
+fn background(r: &Ray, scene: &Scene) -> Color
+{
+    if let Some(environment) = &scene.environment
+    {
+        return environment.emitted(r);
+    }
+    if let Some(background) = scene.background
+    {
+        return sample_color(&background, r);
+    }
+    let unit_direction = r.direction().unit_vector();
+    let t = 0.5*(unit_direction.y() + 1.0);
+    sample_color(&(Color::new(1.0,1.0,1.0).const_mul(1.0-t) + Color::new(0.5,0.7,1.0).const_mul(t)), r)
+}
+
+/**
+ * Next event estimation: samples a direction towards the lights or the environment from the hit point and
+ * returns the light arriving from it, weighted against the scatter pdf with the power heuristic
+ */
+fn sample_lights(r_in: &Ray, rec: &HitRecord, srec: &ScatterRecord, scatter_pdf: &dyn Pdf, scene: &Scene) -> Color
+{
+    let no_light = Color::new(0.0,0.0,0.0);
+    let light_pdf = LightPdf::new(scene, rec.p);
+    if light_pdf.is_empty()
+    {
+        return no_light;
+    }
+
+    let shadow_ray = carry_over(r_in, Ray::new(rec.p, light_pdf.generate()));
+    let light_pdf_value = light_pdf.value(&shadow_ray.direction());
+    let bsdf = rec.mat_ptr.bsdf(r_in, rec, srec, &shadow_ray);
+    if light_pdf_value <= 0.0 || bsdf.near_zero()
+    {
+        return no_light;
+    }
+
+    // The first light along the direction gives the light, or the environment if no light is in the way
+    let mut light_rec = HitRecord::default();
+    let (emitted, light_t) = if scene.lights.hit(&shadow_ray, SPHERE_INTERSECT, INFINITY, &mut light_rec) {
+        (light_rec.mat_ptr.emitted(&shadow_ray, &light_rec), light_rec.t - SPHERE_INTERSECT)
+    } else if let Some(environment) = &scene.environment {
+        (environment.emitted(&shadow_ray), INFINITY)
+    } else {
+        return no_light;
+    };
+    if emitted.near_zero()
+    {
+        return no_light;
+    }
+
+    // Everything between the hit point and the light dims the light
+    let transmittance = scene.world.transmittance(&shadow_ray, SPHERE_INTERSECT, light_t);
+    let weight = power_heuristic(light_pdf_value, scatter_pdf.value(&shadow_ray.direction()));
+
+    (bsdf * emitted * transmittance).const_mul(weight / light_pdf_value)
+}
+
+/**
+ * Multiple importance sampling weight for a sample drawn with pdf f, when g could also have produced it
+ */
+fn power_heuristic(f: f32, g: f32) -> f32
+{
+    if f <= 0.0
+    {
+        return 0.0;
+    }
+    (f*f) / (f*f + g*g)
+}
+
+/**
+ * Clamps each channel of the color to [0,1]
+ */
+fn saturate(c: &Color) -> Color
+{
+    Color::new(clamp(c.x(), 0.0, 1.0), clamp(c.y(), 0.0, 1.0), clamp(c.z(), 0.0, 1.0))
+}
+
+/**
+ * Clamps given value to the given range
+ */
+pub fn clamp(x: f32, min: f32, max: f32) -> f32
+{
+    if x < min {
+        return min;
+    } else if x > max {
+        return max;
+    }
+
+    return x;
+}
+
+/*
+ * Simple function, that hard codes a sphere in the image
+ */
+/*pub fn hit_sphere(center: Point3, radius: f32, r: &Ray) -> f32
+{
+    let oc = r.origin() - center;
+    let a = r.direction().length_squared();
+    let half_b = dot(&oc, &r.direction());
+    let c = oc.length_squared() - (radius*radius);
+    let discriminant = half_b*half_b - a*c;
+
+    // Check if the ray hit the ball
+    if discriminant < 0.0
+    {
+        return -1.0
+    } else 
+    {
+        return (-half_b - discriminant.sqrt()) / a;
+    }
 }*/
\ No newline at end of file
@@ -0,0 +1,88 @@
+use crate::{film::features::Features, objects::{hittable::Hittable, hit_record::HitRecord}, scene::Scene, utility::{counters::take_intersection_tests, rtweekend::INFINITY}};
+
+use super::{color::ray_color, ray::Ray, vec3::Color};
+
+// Objects tested by a ray that show as the hottest color in the cost view
+const MAX_COST: f32 = 1000.0;
+
+/**
+ * Views of a scene that show how it is built and how it renders, in place of its lighting
+ */
+#[derive(Copy, Clone)]
+pub enum DebugMode
+{
+    Normals, // Normal of the first hit, from [-1,1] to [0,1] per axis
+    Mask, // White where a ray hits something, black where it leaves the scene
+    Uv, // Surface coordinates of the first hit as red and green
+    Cost, // Objects tested to find the first hit, as a heat map on a log scale
+    PathDepth // Times the path scattered before it ended, as a heat map up to the maximum depth
+}
+
+impl DebugMode
+{
+    pub fn from_name(name: &str) -> Option<DebugMode>
+    {
+        match name
+        {
+            "normals" => Some(DebugMode::Normals),
+            "mask" => Some(DebugMode::Mask),
+            "uv" => Some(DebugMode::Uv),
+            "cost" => Some(DebugMode::Cost),
+            "path-depth" => Some(DebugMode::PathDepth),
+            _ => None
+        }
+    }
+}
+
+/**
+ * Returns the color of the ray in the given debug view
+ */
+pub fn debug_color(r: &Ray, scene: &Scene, mode: DebugMode, max_depth: i32) -> Color
+{
+    let mut rec = HitRecord::default();
+    match mode
+    {
+        DebugMode::PathDepth => {
+            let mut features = Features::default();
+            ray_color(r, scene, max_depth, &mut features);
+            heat(features.bounces as f32 / max_depth as f32)
+        }
+        DebugMode::Cost => {
+            take_intersection_tests();
+            scene.world.hit(r, 0.001, INFINITY, &mut rec);
+            let tests = take_intersection_tests();
+            heat((1.0 + tests as f32).ln() / (1.0 + MAX_COST).ln())
+        }
+        DebugMode::Normals | DebugMode::Mask | DebugMode::Uv => {
+            if !scene.world.hit(r, 0.001, INFINITY, &mut rec)
+            {
+                return Color::new(0.0,0.0,0.0);
+            }
+            match mode
+            {
+                DebugMode::Normals => (rec.normal + Color::new(1.0,1.0,1.0)).const_mul(0.5),
+                DebugMode::Uv => Color::new(rec.u, rec.v, 0.0),
+                _ => Color::new(1.0,1.0,1.0)
+            }
+        }
+    }
+}
+
+/**
+ * Maps t in [0,1] to a color from blue through green to red
+ */
+fn heat(t: f32) -> Color
+{
+    let t = t.clamp(0.0, 1.0);
+    let ramp = [
+        Color::new(0.0,0.0,1.0),
+        Color::new(0.0,1.0,1.0),
+        Color::new(0.0,1.0,0.0),
+        Color::new(1.0,1.0,0.0),
+        Color::new(1.0,0.0,0.0)
+    ];
+    let position = t * (ramp.len() - 1) as f32;
+    let i = (position as usize).min(ramp.len() - 2);
+    let f = position - i as f32;
+    ramp[i].const_mul(1.0 - f) + ramp[i + 1].const_mul(f)
+}
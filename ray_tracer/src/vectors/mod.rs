@@ -1,3 +1,6 @@
-pub mod vec3;
-pub mod color;
-pub mod ray;
\ No newline at end of file
+pub mod vec3;
+pub mod color;
+pub mod ray;
+pub mod onb;
+pub mod spectrum;
+pub mod debug;
\ No newline at end of file
@@ -0,0 +1,60 @@
+use super::vec3::{Vec3, cross, dot};
+
+/**
+ * An orthonormal basis, with w along the given vector
+ */
+#[derive(Copy, Clone)]
+pub struct Onb
+{
+    axis: [Vec3; 3]
+}
+
+impl Onb
+{
+    /**
+     * Builds a basis around the given vector, which does not need to be normalised
+     */
+    pub fn new(n: &Vec3) -> Onb
+    {
+        let w = n.unit_vector();
+        let a = if w.x().abs() > 0.9 { Vec3::new(0.0,1.0,0.0) } else { Vec3::new(1.0,0.0,0.0) };
+        let v = cross(&w, &a).unit_vector();
+        let u = cross(&w, &v);
+
+        Onb
+        {
+            axis: [u, v, w]
+        }
+    }
+
+    pub fn u(&self) -> Vec3
+    {
+        self.axis[0]
+    }
+
+    pub fn v(&self) -> Vec3
+    {
+        self.axis[1]
+    }
+
+    pub fn w(&self) -> Vec3
+    {
+        self.axis[2]
+    }
+
+    /**
+     * Transforms a vector given in basis coordinates to world coordinates
+     */
+    pub fn local(&self, a: &Vec3) -> Vec3
+    {
+        self.axis[0].const_mul(a.x()) + self.axis[1].const_mul(a.y()) + self.axis[2].const_mul(a.z())
+    }
+
+    /**
+     * Returns the coordinates in the basis of a vector given in world coordinates
+     */
+    pub fn project(&self, a: &Vec3) -> Vec3
+    {
+        Vec3::new(dot(a, &self.axis[0]), dot(a, &self.axis[1]), dot(a, &self.axis[2]))
+    }
+}
@@ -1,47 +1,117 @@
-use super::vec3::Point3;
-use super::vec3::Vec3;
-
-pub struct Ray 
-{
-    origin: Point3,
-    direction: Vec3
-}
-
-impl Default for Ray
-{
-    fn default() -> Ray {
-        Ray {
-            origin: Point3::default(),
-            direction: Vec3::default()
-        }
-    }
-}
-
-impl Ray
-{
-    /**
-     * Returns a new ray with given start point and direction
-     */
-    pub fn new(origin: Point3, direction:Vec3) -> Ray
-    {
-        Ray {
-            origin: origin,
-            direction: direction
-        }
-    }
-
-    pub fn at(&self, t: f32) -> Point3
-    {
-        self.origin + self.direction.const_mul(t)
-    }
-
-    pub fn origin(&self) -> Vec3
-    {
-        self.origin
-    }
-
-    pub fn direction(&self) -> Point3
-    {
-        self.direction
-    }
+use super::vec3::Point3;
+use super::vec3::Vec3;
+
+#[derive(Copy, Clone)]
+pub struct Ray 
+{
+    origin: Point3,
+    direction: Vec3,
+    wavelength: Option<f32>, // Wavelength in nm, once the ray has been split up by dispersion
+    spectrum: Option<Vec3>, // Wavelengths in nm carried by the three channels, when rendering spectrally
+    time: f32 // Moment in the animation the ray belongs to
+}
+
+impl Default for Ray
+{
+    fn default() -> Ray {
+        Ray {
+            origin: Point3::default(),
+            direction: Vec3::default(),
+            wavelength: None,
+            spectrum: None,
+            time: 0.0
+        }
+    }
+}
+
+impl Ray
+{
+    /**
+     * Returns a new ray with given start point and direction
+     */
+    pub fn new(origin: Point3, direction:Vec3) -> Ray
+    {
+        Ray {
+            origin: origin,
+            direction: direction,
+            wavelength: None,
+            spectrum: None,
+            time: 0.0
+        }
+    }
+
+    /**
+     * Returns the ray restricted to a single wavelength
+     */
+    pub fn with_wavelength(self, wavelength: Option<f32>) -> Ray
+    {
+        Ray {
+            wavelength,
+            ..self
+        }
+    }
+
+    /**
+     * Returns the ray carrying the given wavelengths in its three channels, instead of red, green and blue
+     */
+    pub fn with_spectrum(self, spectrum: Option<Vec3>) -> Ray
+    {
+        Ray {
+            spectrum,
+            ..self
+        }
+    }
+
+    /**
+     * Returns the ray at the given moment of the animation
+     */
+    pub fn with_time(self, time: f32) -> Ray
+    {
+        Ray {
+            time,
+            ..self
+        }
+    }
+
+    /**
+     * Returns a ray from a new origin in a new direction, carrying the same wavelengths and time as this one
+     */
+    pub fn continued(&self, origin: Point3, direction: Vec3) -> Ray
+    {
+        Ray {
+            origin,
+            direction,
+            ..*self
+        }
+    }
+
+    pub fn at(&self, t: f32) -> Point3
+    {
+        self.origin + self.direction.const_mul(t)
+    }
+
+    pub fn origin(&self) -> Vec3
+    {
+        self.origin
+    }
+
+    pub fn direction(&self) -> Point3
+    {
+        self.direction
+    }
+
+    pub fn wavelength(&self) -> Option<f32>
+    {
+        self.wavelength
+    }
+
+    pub fn spectrum(&self) -> Option<Vec3>
+    {
+        self.spectrum
+    }
+
+    pub fn time(&self) -> f32
+    {
+        self.time
+    }
 }
\ No newline at end of file
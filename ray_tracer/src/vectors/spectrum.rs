@@ -0,0 +1,177 @@
+use std::sync::OnceLock;
+
+use crate::utility::rtweekend::random_number;
+
+use super::{color::RGB_WAVELENGTHS, ray::Ray, vec3::{Color, Vec3}};
+
+// Visible range that wavelengths are sampled from, in nm
+pub const LAMBDA_MIN: f32 = 380.0;
+pub const LAMBDA_MAX: f32 = 780.0;
+
+// Smits' basis spectra for upsampling RGB, in ten bins from 380 to 720 nm
+const SMITS_WHITE: [f32; 10] = [1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000];
+const SMITS_CYAN: [f32; 10] = [0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000];
+const SMITS_MAGENTA: [f32; 10] = [1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959];
+const SMITS_YELLOW: [f32; 10] = [0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840];
+const SMITS_RED: [f32; 10] = [0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149];
+const SMITS_GREEN: [f32; 10] = [0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025];
+const SMITS_BLUE: [f32; 10] = [1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496];
+const SMITS_MIN: f32 = 380.0;
+const SMITS_MAX: f32 = 720.0;
+
+/**
+ * Hero wavelength sampling: a uniformly random wavelength, and two more spread evenly over the visible range
+ */
+pub fn sample_wavelengths() -> Vec3
+{
+    let range = LAMBDA_MAX - LAMBDA_MIN;
+    let hero = LAMBDA_MIN + random_number() * range;
+    let rotate = |i: f32| {
+        let lambda = hero + i * range / 3.0;
+        if lambda >= LAMBDA_MAX { lambda - range } else { lambda }
+    };
+
+    Vec3::new(hero, rotate(1.0), rotate(2.0))
+}
+
+/**
+ * Returns the color as the ray carries it: unchanged for an RGB ray, or the upsampled spectrum
+ * at the ray's wavelengths if it is a spectral ray
+ */
+pub fn sample_color(rgb: &Color, r: &Ray) -> Color
+{
+    match r.spectrum()
+    {
+        Some(lambda) => Color::new(
+            rgb_to_spectrum(rgb, lambda.x()),
+            rgb_to_spectrum(rgb, lambda.y()),
+            rgb_to_spectrum(rgb, lambda.z())),
+        None => *rgb
+    }
+}
+
+/**
+ * Evaluates Smits' spectrum for the RGB color at the given wavelength
+ */
+pub fn rgb_to_spectrum(rgb: &Color, lambda: f32) -> f32
+{
+    let (r, g, b) = (rgb.x(), rgb.y(), rgb.z());
+    let basis = |table: &[f32; 10]| smits_value(table, lambda);
+
+    if r <= g && r <= b {
+        let mut value = r * basis(&SMITS_WHITE);
+        if g <= b {
+            value += (g - r) * basis(&SMITS_CYAN) + (b - g) * basis(&SMITS_BLUE);
+        } else {
+            value += (b - r) * basis(&SMITS_CYAN) + (g - b) * basis(&SMITS_GREEN);
+        }
+        value
+    } else if g <= r && g <= b {
+        let mut value = g * basis(&SMITS_WHITE);
+        if r <= b {
+            value += (r - g) * basis(&SMITS_MAGENTA) + (b - r) * basis(&SMITS_BLUE);
+        } else {
+            value += (b - g) * basis(&SMITS_MAGENTA) + (r - b) * basis(&SMITS_RED);
+        }
+        value
+    } else {
+        let mut value = b * basis(&SMITS_WHITE);
+        if r <= g {
+            value += (r - b) * basis(&SMITS_YELLOW) + (g - r) * basis(&SMITS_GREEN);
+        } else {
+            value += (g - b) * basis(&SMITS_YELLOW) + (r - g) * basis(&SMITS_RED);
+        }
+        value
+    }
+}
+
+/**
+ * Linear interpolation between the bin centers of a basis spectrum
+ */
+fn smits_value(table: &[f32; 10], lambda: f32) -> f32
+{
+    let bin_width = (SMITS_MAX - SMITS_MIN) / table.len() as f32;
+    let x = ((lambda - SMITS_MIN) / bin_width - 0.5).clamp(0.0, (table.len() - 1) as f32);
+    let i = (x as usize).min(table.len() - 2);
+    let t = x - i as f32;
+    table[i] * (1.0 - t) + table[i + 1] * t
+}
+
+/**
+ * CIE 1931 color matching functions, using the multi-lobe fit of Wyman, Sloan and Shirley
+ */
+fn cie_xyz(lambda: f32) -> Vec3
+{
+    let g = |mu: f32, sigma1: f32, sigma2: f32| {
+        let t = (lambda - mu) / if lambda < mu { sigma1 } else { sigma2 };
+        (-0.5 * t * t).exp()
+    };
+
+    Vec3::new(
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8))
+}
+
+/**
+ * Converts CIE XYZ to linear sRGB, with the D65 white point
+ */
+pub fn xyz_to_linear_srgb(xyz: &Vec3) -> Color
+{
+    Color::new(
+        3.240_454 * xyz.x() - 1.537_138_5 * xyz.y() - 0.498_531_4 * xyz.z(),
+        -0.969_266 * xyz.x() + 1.876_010_8 * xyz.y() + 0.041_556 * xyz.z(),
+        0.055_643_4 * xyz.x() - 0.204_025_9 * xyz.y() + 1.057_225_2 * xyz.z())
+}
+
+/**
+ * The linear sRGB response to a constant spectrum of 1, which is scaled back to white
+ */
+fn white_point() -> Color
+{
+    static WHITE: OnceLock<(f32, f32, f32)> = OnceLock::new();
+    let (r, g, b) = *WHITE.get_or_init(|| {
+        let mut xyz = Vec3::new(0.0, 0.0, 0.0);
+        let steps = 400;
+        let step = (LAMBDA_MAX - LAMBDA_MIN) / steps as f32;
+        for i in 0..steps
+        {
+            xyz = xyz + cie_xyz(LAMBDA_MIN + (i as f32 + 0.5) * step).const_mul(step);
+        }
+        let white = xyz_to_linear_srgb(&xyz);
+        (white.x(), white.y(), white.z())
+    });
+    Color::new(r, g, b)
+}
+
+/**
+ * Converts the radiance carried at the sampled wavelengths into a linear RGB estimate for the pixel
+ */
+pub fn spectrum_to_rgb(values: &Color, wavelengths: &Vec3) -> Color
+{
+    // Monte Carlo estimate of the XYZ integrals, with the uniform wavelength pdf
+    let scale = (LAMBDA_MAX - LAMBDA_MIN) / 3.0;
+    let xyz = (cie_xyz(wavelengths.x()).const_mul(values.x())
+        + cie_xyz(wavelengths.y()).const_mul(values.y())
+        + cie_xyz(wavelengths.z()).const_mul(values.z())).const_mul(scale);
+
+    let rgb = xyz_to_linear_srgb(&xyz);
+    let white = white_point();
+    Color::new(rgb.x() / white.x(), rgb.y() / white.y(), rgb.z() / white.z())
+}
+
+/**
+ * Returns the wavelength in nm that each channel of the ray stands for
+ */
+pub fn channel_wavelengths(r: &Ray) -> Vec3
+{
+    if let Some(lambda) = r.wavelength()
+    {
+        return Vec3::new(lambda, lambda, lambda);
+    }
+    match r.spectrum()
+    {
+        Some(spectrum) => spectrum,
+        None => Vec3::new(RGB_WAVELENGTHS[0], RGB_WAVELENGTHS[1], RGB_WAVELENGTHS[2])
+    }
+}